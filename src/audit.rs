@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+
+use crate::paths;
+
+/// Kind of mutation sent to the TickTick API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Complete,
+    Delete,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Complete => "complete",
+            AuditAction::Delete => "delete",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One line of the append-only audit log
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Local>,
+    pub action: AuditAction,
+    pub project_id: Option<String>,
+    pub task_id: Option<String>,
+    /// Short description of the payload, e.g. the task title
+    pub summary: String,
+    pub status: u16,
+}
+
+/// Append an entry to the audit log
+pub fn record(entry: &AuditEntry) -> Result<()> {
+    let log_path = paths::audit_log()?;
+    paths::append(&log_path, &format!("{}\n", serde_json::to_string(entry)?))
+}
+
+/// Read every entry in the audit log, oldest first
+pub fn read_entries() -> Result<Vec<AuditEntry>> {
+    let log_path = paths::audit_log()?;
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&log_path)
+        .map_err(|e| anyhow!("Failed to read audit log {}: {}", log_path.display(), e))?;
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| anyhow!("Malformed audit log entry: {}", e))
+        })
+        .collect()
+}
@@ -0,0 +1,292 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+use dtparse::parse;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::deps;
+use crate::paths;
+use crate::types::{Project, Task};
+
+/// What this process changed since loading the cache, replayed onto the cache file when another
+/// tick process saved it in the meantime
+#[derive(Debug, Default)]
+struct Changes {
+    projects: bool,
+    all_tasks: bool,
+    /// Projects whose task list was replaced
+    project_tasks: HashSet<String>,
+    /// Tasks inserted, updated or removed one at a time
+    task_ids: HashSet<String>,
+    task_filter_missing: bool,
+}
+
+/// Locally cached API data, used to avoid repeating requests
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Cache {
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    /// Tasks keyed by the id of the project they were fetched from
+    #[serde(default)]
+    pub tasks: HashMap<String, Vec<Task>>,
+    /// Titles of the open tasks each blocked task depends on, keyed by task id
+    #[serde(default)]
+    pub blocked_by: HashMap<String, Vec<String>>,
+    pub updated_at: Option<DateTime<Local>>,
+    /// When the API last turned out not to have the task filter endpoint, so it isn't tried
+    /// on every run
+    #[serde(default)]
+    pub task_filter_missing_at: Option<DateTime<Local>>,
+    /// Bumped by every save, to notice another tick process saving after this one loaded
+    #[serde(default)]
+    revision: u64,
+    #[serde(skip)]
+    changes: Changes,
+}
+
+impl Cache {
+    /// Load the cache, falling back to an empty cache if it is missing or unreadable. No lock
+    /// is taken: the file is only ever replaced whole, so it can't be read half-written.
+    pub fn load() -> Self {
+        match paths::cache_file() {
+            Ok(cache_path) => Self::read(&cache_path),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn read(cache_path: &Path) -> Self {
+        fs::read_to_string(cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the cache atomically, locking it only while it is re-read and written. If another
+    /// tick process saved it since this one loaded it, this process's changes are applied to
+    /// that copy instead, so neither loses its changes.
+    pub fn save(&mut self) -> Result<()> {
+        let cache_path = paths::cache_file()?;
+        let _lock = paths::lock(&cache_path)?;
+
+        let saved = Self::read(&cache_path);
+        if saved.revision != self.revision {
+            *self = self.applied_to(saved);
+        }
+        self.revision += 1;
+        self.changes = Changes::default();
+
+        let cache_content =
+            serde_json::to_string(self).map_err(|e| anyhow!("Failed to serialize cache: {}", e))?;
+        paths::write_atomic_locked(&cache_path, &cache_content)
+    }
+
+    /// `saved` with this cache's changes made to it
+    fn applied_to(&self, mut saved: Cache) -> Cache {
+        let changes = &self.changes;
+        if changes.projects {
+            saved.projects = self.projects.clone();
+        }
+        if changes.all_tasks {
+            saved.tasks = self.tasks.clone();
+        }
+        for project_id in &changes.project_tasks {
+            let tasks = self.tasks.get(project_id).cloned().unwrap_or_default();
+            saved.tasks.insert(project_id.clone(), tasks);
+        }
+        for id in &changes.task_ids {
+            saved.remove_task(id);
+            if let Some(task) = self.tasks.values().flatten().find(|task| &task.id == id) {
+                saved.upsert_task(task);
+            }
+        }
+        if changes.task_filter_missing {
+            saved.task_filter_missing_at = self.task_filter_missing_at;
+        }
+        saved.updated_at = saved.updated_at.max(self.updated_at);
+        saved.refresh_dependencies();
+        saved
+    }
+
+    pub fn set_projects(&mut self, projects: &[Project]) {
+        self.projects = projects.to_vec();
+        self.updated_at = Some(Local::now());
+        self.changes.projects = true;
+    }
+
+    pub fn set_project_tasks(&mut self, project_id: &str, tasks: &[Task]) {
+        self.tasks.insert(project_id.to_string(), tasks.to_vec());
+        self.updated_at = Some(Local::now());
+        self.changes.project_tasks.insert(project_id.to_string());
+    }
+
+    /// Remember when the task filter endpoint turned out to be missing, or None once it works
+    pub fn set_task_filter_missing(&mut self, at: Option<DateTime<Local>>) {
+        self.task_filter_missing_at = at;
+        self.changes.task_filter_missing = true;
+    }
+
+    /// Replace the tasks of every project with `tasks`, fetched across all projects at once.
+    /// Projects without open tasks get empty lists; tasks of projects that aren't in
+    /// `projects`, e.g. archived ones, are left out just like the per-project fetch does.
+    pub fn set_all_tasks(&mut self, projects: &[Project], tasks: &[Task]) {
+        let mut grouped: HashMap<String, Vec<Task>> = projects
+            .iter()
+            .map(|project| (project.id.clone(), Vec::new()))
+            .collect();
+        grouped.insert("inbox".to_string(), Vec::new());
+
+        for task in tasks {
+            let key = if task.project_id.starts_with("inbox") {
+                "inbox"
+            } else {
+                task.project_id.as_str()
+            };
+            if let Some(project_tasks) = grouped.get_mut(key) {
+                project_tasks.push(task.clone());
+            }
+        }
+
+        self.tasks = grouped;
+        self.updated_at = Some(Local::now());
+        self.changes.all_tasks = true;
+    }
+
+    /// Re-resolve `depends:` lines against the cached open tasks
+    pub fn refresh_dependencies(&mut self) {
+        let tasks: Vec<&Task> = self.tasks.values().flatten().collect();
+        self.blocked_by = deps::blocked_by(&tasks);
+    }
+
+    /// Find a cached task by id or unique id prefix
+    pub fn find_task(&self, id: &str) -> Result<Option<&Task>> {
+        let mut matches = self
+            .tasks
+            .values()
+            .flatten()
+            .filter(|task| task.id.starts_with(id));
+
+        match (matches.next(), matches.next()) {
+            (Some(_), Some(_)) => Err(anyhow!("Task id '{}' is ambiguous", id)),
+            (task, _) => Ok(task),
+        }
+    }
+
+    /// Find a cached project by case-insensitive name or id
+    pub fn find_project(&self, name: &str) -> Option<&Project> {
+        self.projects
+            .iter()
+            .find(|project| project.id == name || project.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Insert a task, replacing any cached copy with the same id
+    pub fn upsert_task(&mut self, task: &Task) {
+        self.changes.task_ids.insert(task.id.clone());
+        for tasks in self.tasks.values_mut() {
+            if let Some(existing) = tasks.iter_mut().find(|existing| existing.id == task.id) {
+                *existing = task.clone();
+                return;
+            }
+        }
+
+        let key = if task.project_id.starts_with("inbox") {
+            "inbox"
+        } else {
+            task.project_id.as_str()
+        };
+        self.tasks
+            .entry(key.to_string())
+            .or_default()
+            .push(task.clone());
+    }
+
+    pub fn remove_task(&mut self, id: &str) {
+        self.changes.task_ids.insert(id.to_string());
+        for tasks in self.tasks.values_mut() {
+            tasks.retain(|task| task.id != id);
+        }
+    }
+
+    /// Most recent task modification seen in a project, used to rank project activity
+    pub fn last_activity(&self, project_id: &str) -> Option<NaiveDateTime> {
+        self.tasks
+            .get(project_id)?
+            .iter()
+            .filter_map(|task| task.modified_time.as_deref())
+            .filter_map(|modified| parse(modified).ok().map(|(datetime, _)| datetime))
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, title: &str) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "projectId": "project",
+            "title": title,
+            "status": 0,
+        }))
+        .unwrap()
+    }
+
+    fn titles(cache: &Cache) -> Vec<(String, String)> {
+        let mut titles: Vec<_> = cache
+            .tasks
+            .values()
+            .flatten()
+            .map(|task| (task.id.clone(), task.title.clone()))
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn changes_apply_to_a_cache_saved_meanwhile() {
+        let mut loaded = Cache::default();
+        loaded.set_project_tasks("project", &[task("a", "A"), task("b", "B"), task("c", "C")]);
+        loaded.changes = Changes::default();
+
+        // Another process renamed `a` and added `d` after this one loaded the cache
+        let mut saved = Cache {
+            tasks: loaded.tasks.clone(),
+            revision: 1,
+            ..Default::default()
+        };
+        saved.upsert_task(&task("a", "A renamed"));
+        saved.upsert_task(&task("d", "D"));
+
+        let mut mine = loaded;
+        mine.upsert_task(&task("b", "B edited"));
+        mine.remove_task("c");
+
+        let merged = mine.applied_to(saved);
+        assert_eq!(merged.revision, 1);
+        assert_eq!(
+            titles(&merged),
+            [
+                ("a".to_string(), "A renamed".to_string()),
+                ("b".to_string(), "B edited".to_string()),
+                ("d".to_string(), "D".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaced_task_lists_win() {
+        let mut saved = Cache::default();
+        saved.set_project_tasks("project", &[task("a", "A")]);
+        saved.set_task_filter_missing(Some(Local::now()));
+
+        let mut mine = Cache::default();
+        mine.set_project_tasks("project", &[task("b", "B")]);
+
+        let merged = mine.applied_to(saved);
+        assert_eq!(titles(&merged), [("b".to_string(), "B".to_string())]);
+        // Untouched by this process, so the other one's value stays
+        assert!(merged.task_filter_missing_at.is_some());
+    }
+}
@@ -0,0 +1,499 @@
+use clap::{ArgAction, ArgGroup, Args, Parser, Subcommand, ValueEnum};
+
+use crate::config::{ProjectOrder, TaskOrder};
+use crate::dedup::OnDuplicate;
+use crate::help;
+use crate::notify::NotifyTarget;
+use crate::types::Priority;
+
+/// View your TickTick tasks from the terminal
+#[derive(Debug, Parser)]
+#[command(name = "tick", version, about, after_long_help = help::OVERVIEW)]
+pub struct Cli {
+    /// Maximum number of API requests this invocation may issue
+    #[arg(long, global = true, value_name = "N")]
+    pub max_requests: Option<usize>,
+
+    /// Only fetch these projects, e.g. "Inbox,Work"; overrides `sync.project_scope`
+    #[arg(long, global = true, value_name = "PROJECTS", value_delimiter = ',')]
+    pub project_scope: Vec<String>,
+
+    /// Change tasks even if they were edited elsewhere since tick last fetched them
+    #[arg(long, global = true)]
+    pub force: bool,
+
+    /// Fail on malformed API data, printing the offending JSON, instead of skipping it
+    #[arg(long, global = true)]
+    pub strict_parse: bool,
+
+    /// Order of project sections, overriding `display.project_order`
+    #[arg(long, global = true, value_name = "ORDER")]
+    pub project_order: Option<ProjectOrder>,
+
+    /// Order of tasks within a project, overriding `display.task_order`
+    #[arg(long, global = true, value_name = "ORDER")]
+    pub task_order: Option<TaskOrder>,
+
+    /// Hide completed checklist items, showing only their progress bar
+    #[arg(long, global = true)]
+    pub hide_done_items: bool,
+
+    /// Show the full content of every task instead of the first few lines
+    #[arg(long, global = true)]
+    pub expand: bool,
+
+    /// Write API request and cache metrics to this file in the Prometheus text format when
+    /// the command finishes, e.g. for node_exporter's textfile collector
+    #[arg(long, global = true, value_name = "FILE")]
+    pub metrics_file: Option<std::path::PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Show tasks due today and overdue tasks (default)
+    Today,
+    /// Guided first-run setup: register an app, authorize and test the connection
+    Init,
+    /// Create a new task
+    #[command(after_long_help = help::DATES)]
+    Add(AddArgs),
+    /// Type tasks one per line, e.g. `Call bank ~Finance #phone !high @tomorrow`, until Ctrl-D
+    #[command(after_long_help = help::QUICK_ADD)]
+    Capture {
+        /// Project for tasks without a ~project (defaults to the inbox)
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+    /// Change an existing task
+    #[command(after_long_help = help::DATES)]
+    Edit(EditArgs),
+    /// Show every detail of a task
+    Show {
+        /// Task id, a unique prefix of it, or its number in the last listing
+        task_id: String,
+    },
+    /// Explain why a task does or doesn't show up in `tick today`
+    Explain {
+        /// Task id, a unique prefix of it, or its number in the last listing
+        task_id: String,
+    },
+    /// Add a timestamped bullet to a task's notes, e.g. `tick note 3 called the plumber`
+    Note {
+        /// Task id, a unique prefix of it, or its number in the last listing
+        task_id: String,
+        /// The note; the words are joined with spaces
+        #[arg(required = true, trailing_var_arg = true)]
+        text: Vec<String>,
+    },
+    /// Mark a task as completed
+    Done {
+        /// Task id, a unique prefix of it, or a number or range like 2-5 from the last listing
+        task_id: String,
+    },
+    /// Delete a task
+    Delete {
+        /// Task id, a unique prefix of it, or a number or range like 2-5 from the last listing
+        task_id: String,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Push a task's due date back, e.g. `1d`, `2w`, `3h`, `tomorrow` or `2024-05-01`
+    #[command(after_long_help = help::SHIFTS)]
+    Snooze {
+        /// Task id, a unique prefix of it, or a number or range like 2-5 from the last listing
+        task_id: String,
+        /// How far to snooze, or the date to snooze until
+        #[arg(required_unless_present = "auto", conflicts_with = "auto")]
+        when: Option<String>,
+        /// Suggest the day with the fewest tasks due soon, and ask before moving the task
+        #[arg(long)]
+        auto: bool,
+        /// Number of days, starting tomorrow, that --auto chooses from (1 to 365)
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 7,
+            requires = "auto",
+            value_parser = clap::value_parser!(u64).range(1..=365)
+        )]
+        within: u64,
+    },
+    /// Change every task matching a filter expression
+    #[command(after_long_help = help::FILTERS)]
+    Bulk(BulkArgs),
+    /// Move a task up or down within its project, as it's ordered in the app
+    Reorder(ReorderArgs),
+    /// List open tasks, optionally narrowed by a saved or ad-hoc filter
+    #[command(after_long_help = help::FILTERS)]
+    List {
+        /// Name of a filter saved under [filters.<name>] in the config
+        name: Option<String>,
+        /// Filter expression, e.g. "priority:high OR overdue"
+        #[arg(short, long, conflicts_with = "name")]
+        filter: Option<String>,
+    },
+    /// Search task content and descriptions in the local cache with a regular expression
+    Grep(GrepArgs),
+    /// List tasks waiting on open `depends:` dependencies
+    #[command(after_long_help = help::FILTERS)]
+    Blocked {
+        /// Only consider tasks matching this filter expression
+        #[arg(short, long)]
+        filter: Option<String>,
+    },
+    /// List open tasks that aren't blocked by any dependency
+    #[command(after_long_help = help::FILTERS)]
+    Ready {
+        /// Only consider tasks matching this filter expression
+        #[arg(short, long)]
+        filter: Option<String>,
+    },
+    /// Show this week's tasks as a calendar grid
+    Week {
+        /// Show the following week (repeat to go further ahead)
+        #[arg(long, action = ArgAction::Count, conflicts_with = "prev")]
+        next: u8,
+        /// Show the previous week (repeat to go further back)
+        #[arg(long, action = ArgAction::Count)]
+        prev: u8,
+    },
+    /// Show a month calendar shaded by the number of tasks due each day
+    Month {
+        /// Shade by tasks completed instead of tasks due
+        #[arg(long)]
+        completed: bool,
+        /// Show the following month (repeat to go further ahead)
+        #[arg(long, action = ArgAction::Count, conflicts_with = "prev")]
+        next: u8,
+        /// Show the previous month (repeat to go further back)
+        #[arg(long, action = ArgAction::Count)]
+        prev: u8,
+    },
+    /// Print a daily summary of overdue, today's and upcoming tasks, e.g. to pipe into sendmail
+    Digest {
+        #[arg(long, value_enum, default_value_t = DigestFormat::Text)]
+        format: DigestFormat,
+        /// Post the digest to a configured webhook instead of printing it
+        #[arg(long, value_enum)]
+        notify: Option<NotifyTarget>,
+    },
+    /// Create tasks from another source
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Create tasks from `TODO(tick):` comments in source code
+    Todos {
+        #[command(subcommand)]
+        action: TodosAction,
+    },
+    /// Mirror GitHub issues assigned to you into tasks
+    Github {
+        #[command(subcommand)]
+        action: GithubAction,
+    },
+    /// Create tasks from unseen messages in an IMAP mailbox
+    Mail {
+        #[command(subcommand)]
+        action: MailAction,
+    },
+    /// Publish task counts and the next due task to MQTT, with Home Assistant discovery
+    Mqtt(MqttArgs),
+    /// Manage the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Show every file and directory tick reads or writes, and whether it is usable
+    Paths,
+    /// Write man pages for tick and each command, e.g. into /usr/local/share/man/man1
+    Man {
+        /// Directory to write the pages to
+        #[arg(default_value = ".")]
+        dir: std::path::PathBuf,
+    },
+    /// Time how long you spend on tasks
+    Track {
+        #[command(subcommand)]
+        action: TrackAction,
+    },
+    /// Sum up tracked time and completed tasks
+    Stats {
+        /// Number of days to include, counting today (1-3650)
+        #[arg(
+            short,
+            long,
+            default_value_t = 7,
+            value_parser = clap::value_parser!(u32).range(1..=3650)
+        )]
+        days: u32,
+    },
+    /// Show the audit log of changes made through tick
+    Log {
+        /// Number of most recent entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GrepArgs {
+    /// Regular expression, e.g. "invoice|receipt"
+    pub pattern: String,
+    /// Match regardless of case
+    #[arg(short, long)]
+    pub ignore_case: bool,
+    /// Lines of context to show after each match
+    #[arg(short = 'A', long, value_name = "N")]
+    pub after_context: Option<usize>,
+    /// Lines of context to show before each match
+    #[arg(short = 'B', long, value_name = "N")]
+    pub before_context: Option<usize>,
+    /// Lines of context to show before and after each match
+    #[arg(short = 'C', long, value_name = "N")]
+    pub context: Option<usize>,
+    /// Only list the matching tasks, not the lines that matched
+    #[arg(short = 'l', long)]
+    pub tasks_with_matches: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+#[command(group(ArgGroup::new("position").required(true).args(["up", "down", "top", "bottom"])))]
+pub struct ReorderArgs {
+    /// Task id, a unique prefix of it, or its number in the last listing
+    pub task_id: String,
+    /// Move up this many places (default 1)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    pub up: Option<usize>,
+    /// Move down this many places (default 1)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    pub down: Option<usize>,
+    /// Move to the top of the project
+    #[arg(long)]
+    pub top: bool,
+    /// Move to the bottom of the project
+    #[arg(long)]
+    pub bottom: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DigestFormat {
+    Text,
+    Html,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigAction {
+    /// Print the value of a key, e.g. `display.time_style`
+    Get { key: String },
+    /// Validate and set a key, e.g. `tick config set display.time_style relative`. Leave out
+    /// the value of a secret such as mail.password to type it at a prompt instead, keeping it
+    /// out of shell history; secrets are stored the same way as the tokens.
+    Set { key: String, value: Option<String> },
+    /// Open the configuration file in $VISUAL or $EDITOR, validating it before saving
+    Edit,
+    /// Encrypt the tokens and secrets stored in the configuration file with a passphrase
+    Encrypt,
+    /// Store the tokens and secrets in the configuration file unencrypted again
+    Decrypt,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ImportSource {
+    /// Create a task for each calendar event in an .ics file
+    Ics(IcsImportArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct IcsImportArgs {
+    /// Path to the .ics file
+    pub file: std::path::PathBuf,
+    /// Project name (defaults to the inbox)
+    #[arg(short, long)]
+    pub project: Option<String>,
+    /// Only import events starting on or after this date (defaults to today)
+    #[arg(long)]
+    pub from: Option<String>,
+    /// Only import events starting on or before this date (defaults to 30 days from today)
+    #[arg(long)]
+    pub to: Option<String>,
+    /// List the events that would be imported without creating tasks
+    #[arg(long)]
+    pub dry_run: bool,
+    #[command(flatten)]
+    pub duplicates: DuplicateArgs,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum TodosAction {
+    /// Create a task for each new `TODO(tick):` comment and tag the comment with its id
+    Scan(TodosScanArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TodosScanArgs {
+    /// File or directory to scan
+    #[arg(default_value = ".")]
+    pub path: std::path::PathBuf,
+    /// Project name, overriding `todos.project` (defaults to the inbox)
+    #[arg(short, long)]
+    pub project: Option<String>,
+    /// List the comments that would become tasks without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum GithubAction {
+    /// Create tasks for open issues assigned to you and complete tasks whose issue was closed
+    Sync(GithubSyncArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GithubSyncArgs {
+    /// Repository as `owner/name`
+    #[arg(long)]
+    pub repo: String,
+    /// Project name for new tasks (defaults to the inbox)
+    #[arg(short, long)]
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum TrackAction {
+    /// Start a timer on a task, stopping the one that's running
+    Start {
+        /// Task id, a unique prefix of it, or its number in the last listing
+        task_id: String,
+    },
+    /// Stop the running timer and log the time
+    Stop {
+        /// Also add a line with the tracked time to the task's notes
+        #[arg(long)]
+        append: bool,
+    },
+    /// Show the running timer
+    Status,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum MailAction {
+    /// Create a task for each unseen message in the `[mail]` folder, then mark it seen
+    Fetch(MailFetchArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MailFetchArgs {
+    /// Project name, overriding `mail.project` (defaults to the inbox)
+    #[arg(short, long)]
+    pub project: Option<String>,
+    /// List the messages that would become tasks without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MqttArgs {
+    /// Broker address, e.g. `localhost`, `broker.lan:1883`, `mqtt://broker.lan:1883` or `[::1]:1883`
+    #[arg(long)]
+    pub broker: String,
+    /// User to log in as; the password comes from TICK_MQTT_PASSWORD, or else a prompt
+    #[arg(long)]
+    pub username: Option<String>,
+    /// State is published to `<prefix>/state`
+    #[arg(long, default_value = "tick")]
+    pub topic_prefix: String,
+    /// Home Assistant discovery prefix
+    #[arg(long, default_value = "homeassistant")]
+    pub discovery_prefix: String,
+    /// Don't publish Home Assistant discovery messages
+    #[arg(long)]
+    pub no_discovery: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AddArgs {
+    /// Task title
+    #[arg(required_unless_present_any = ["from_clipboard", "json_stdin"])]
+    pub title: Vec<String>,
+    /// Project name (defaults to the inbox)
+    #[arg(short, long)]
+    pub project: Option<String>,
+    /// Due date, e.g. "2024-05-01" or "May 1 5pm"
+    #[arg(short, long)]
+    pub due: Option<String>,
+    #[arg(long, value_enum)]
+    pub priority: Option<Priority>,
+    /// Task notes
+    #[arg(short, long)]
+    pub content: Option<String>,
+    /// Use the clipboard's first line as the title and the rest as notes; a URL becomes
+    /// "Read: <page title>"
+    #[arg(long, conflicts_with_all = ["title", "content"])]
+    pub from_clipboard: bool,
+    /// Create every task in a JSON array read from stdin; --project is the default project
+    #[arg(long, conflicts_with_all = ["title", "content", "due", "priority", "from_clipboard"])]
+    pub json_stdin: bool,
+    /// Don't ask about due dates in the past, over a year away, or at midnight by mistake
+    #[arg(short, long)]
+    pub yes: bool,
+    #[command(flatten)]
+    pub duplicates: DuplicateArgs,
+}
+
+/// How `add` and `import` treat tasks that look like ones you already have
+#[derive(Debug, Clone, Args)]
+pub struct DuplicateArgs {
+    /// What to do when a cached open task has the same title and due date
+    #[arg(long, value_enum, value_name = "POLICY", default_value_t = OnDuplicate::Create)]
+    pub on_duplicate: OnDuplicate,
+    /// Only count a task as a duplicate when its notes match too
+    #[arg(long)]
+    pub match_content: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct EditArgs {
+    /// Task id, a unique prefix of it, or its number in the last listing
+    pub task_id: String,
+    #[arg(long)]
+    pub title: Option<String>,
+    /// Due date, e.g. "2024-05-01" or "May 1 5pm"
+    #[arg(short, long)]
+    pub due: Option<String>,
+    #[arg(long, value_enum)]
+    pub priority: Option<Priority>,
+    /// Task notes, replacing the existing notes
+    #[arg(short, long)]
+    pub content: Option<String>,
+    /// Don't ask about due dates in the past, over a year away, or at midnight by mistake
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BulkArgs {
+    /// Filter expression, e.g. "project:Work AND overdue"
+    #[arg(short, long)]
+    pub filter: String,
+    /// Apply the changes without asking for confirmation after the preview
+    #[arg(short, long)]
+    pub yes: bool,
+    #[command(subcommand)]
+    pub action: BulkAction,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum BulkAction {
+    /// Push due dates back, e.g. `1d`, `2w`, `3h`, `tomorrow` or `2024-05-01`
+    Snooze { when: String },
+    /// Set the priority of every matching task
+    SetPriority {
+        #[arg(value_enum)]
+        priority: Priority,
+    },
+}
@@ -1,37 +1,442 @@
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine as _};
-use chrono::{Local, TimeZone};
-use dtparse::parse;
-use reqwest::{header, Client, RequestBuilder, Response};
-use serde_json;
+use chrono::Local;
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
 use url::Url;
 
 use crate::{
-    types::{InboxData, Project, ProjectData, Task, TokenResponse},
+    audit::{self, AuditAction, AuditEntry},
+    cache::Cache,
+    dates::DateWindow,
+    explain::TodayCheck,
+    metrics::METRICS,
+    types::{InboxData, NewTask, OAuthToken, Project, ProjectData, Task, TokenResponse},
     Config,
 };
 
+/// Caps the number of HTTP requests a single invocation may issue
+struct RequestBudget {
+    max: usize,
+    used: AtomicUsize,
+}
+
+impl RequestBudget {
+    fn try_acquire(&self) -> bool {
+        self.used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                (used < self.max).then_some(used + 1)
+            })
+            .is_ok()
+    }
+
+    fn remaining(&self) -> usize {
+        self.max.saturating_sub(self.used.load(Ordering::SeqCst))
+    }
+}
+
+/// Tally of response items that failed to deserialize or carried unexpected fields
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    pub skipped: usize,
+    pub unknown_fields: BTreeSet<String>,
+}
+
+/// Remove an array field from a JSON object so its items can be parsed individually
+fn take_array(value: &mut Value, key: &str) -> Vec<Value> {
+    match value.get_mut(key).map(Value::take) {
+        Some(Value::Array(items)) => items,
+        _ => Vec::new(),
+    }
+}
+
+/// How long an idle connection is kept for reuse unless `http.pool_idle_timeout` says otherwise.
+/// reqwest's own default, and far longer than the gap between two requests of one run.
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Interval of TCP keep-alive probes, so pooled connections aren't dropped silently by NAT.
+/// Below the pool idle timeout, and well below the few minutes common NAT tables allow.
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Days before an API found without the task filter endpoint is checked for it again
+const TASK_FILTER_RECHECK_DAYS: i64 = 7;
+
+/// Refresh tokens this close to expiry
+fn refresh_margin() -> chrono::Duration {
+    chrono::Duration::minutes(10)
+}
+
+/// How often the background refresh checks the token. Well inside the refresh margin, so a
+/// token is refreshed several minutes before it expires.
+const TOKEN_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps the access token fresh in the background until it's dropped
+pub struct TokenRefresh(JoinHandle<()>);
+
+impl Drop for TokenRefresh {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Every `check_every`, swap in a token from `refresh` if the current one is about to expire.
+/// A failed refresh is tried again on the next check and only reported the first time.
+fn spawn_token_refresh<F, Fut>(
+    token: Arc<RwLock<Option<OAuthToken>>>,
+    check_every: Duration,
+    refresh: F,
+) -> TokenRefresh
+where
+    F: Fn(String) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<OAuthToken>> + Send,
+{
+    TokenRefresh(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(check_every);
+        let mut reported = false;
+        loop {
+            interval.tick().await;
+            let refresh_token = token
+                .read()
+                .unwrap()
+                .as_ref()
+                .filter(|current| current.expires_within(refresh_margin()))
+                .and_then(|current| current.refresh_token.clone());
+            let Some(refresh_token) = refresh_token else {
+                continue;
+            };
+
+            match refresh(refresh_token).await {
+                Ok(new_token) => {
+                    *token.write().unwrap() = Some(new_token);
+                    reported = false;
+                }
+                Err(e) if !reported => {
+                    eprintln!("⚠️ Background token refresh failed, will retry: {}", e);
+                    reported = true;
+                }
+                Err(_) => {}
+            }
+        }
+    }))
+}
+
+/// Exchange a refresh token for a new access token
+async fn request_token_refresh(
+    http: &Client,
+    client_id: &str,
+    client_secret: &str,
+    refresh_token: &str,
+) -> Result<OAuthToken> {
+    let auth_header = general_purpose::STANDARD.encode(format!("{}:{}", client_id, client_secret));
+    let params = [
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+    ];
+
+    let response = http
+        .post("https://ticktick.com/oauth/token")
+        .header(header::AUTHORIZATION, format!("Basic {}", auth_header))
+        .form(&params)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        let token_response: TokenResponse = response.json().await?;
+        let mut token = OAuthToken::from(token_response);
+        // Keep using the old refresh token if the server didn't rotate it
+        token
+            .refresh_token
+            .get_or_insert_with(|| refresh_token.to_string());
+        Ok(token)
+    } else {
+        let error_text = response.text().await?;
+        Err(anyhow!("Failed to refresh access token: {}", error_text))
+    }
+}
+
+/// Tasks due today or overdue, which may be partial when a request budget is set
+pub struct TodaysTasks {
+    pub tasks: Vec<Task>,
+    pub projects: Vec<Project>,
+    /// Names of projects that were not fetched because the request budget ran out
+    pub skipped_projects: Vec<String>,
+    /// The budget ran out before the project list could be fetched, so no project besides
+    /// the inbox was checked
+    pub projects_unknown: bool,
+}
+
 pub struct TickTickClient {
-    client: Client,
-    pub access_token: Option<String>,
+    /// Built on first use, so commands that never reach the network don't pay for TLS setup
+    client: OnceLock<Client>,
+    pool_idle_timeout: Duration,
+    /// Shared with the background refresh, so the token can rotate while requests are in flight
+    token: Arc<RwLock<Option<OAuthToken>>>,
     client_id: String,
     client_secret: String,
     redirect_uri: String,
+    budget: Option<RequestBudget>,
+    /// Lowercase names of the only projects to fetch; "inbox" stands for the inbox
+    project_scope: Option<Vec<String>>,
+    strict_parse: bool,
+    parse_report: Mutex<ParseReport>,
+    /// Set when the API answers 401, so the caller can re-authorize and retry
+    token_rejected: AtomicBool,
+    /// One lock per task id, so changes to the same task are sent one after another
+    write_queue: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Overwrite tasks even when they changed on the server since they were fetched
+    force: bool,
 }
 
 impl TickTickClient {
     pub fn new(client_id: String, client_secret: String, redirect_uri: String) -> Self {
         Self {
-            client: Client::new(),
-            access_token: None,
+            client: OnceLock::new(),
+            pool_idle_timeout: DEFAULT_POOL_IDLE_TIMEOUT,
+            token: Arc::new(RwLock::new(None)),
             client_id,
             client_secret,
             redirect_uri,
+            budget: None,
+            project_scope: None,
+            strict_parse: false,
+            parse_report: Mutex::new(ParseReport::default()),
+            token_rejected: AtomicBool::new(false),
+            write_queue: Mutex::new(HashMap::new()),
+            force: false,
+        }
+    }
+
+    /// Shared HTTP client. A run fetches one project after another from the same host, so the
+    /// connection (HTTP/2 when the server offers it) is kept open and reused instead of paying
+    /// for a TLS handshake per request.
+    fn http(&self) -> Result<&Client> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = Client::builder()
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(TCP_KEEPALIVE)
+            // Requests are small and sent one at a time, so don't hold them back to batch
+            .tcp_nodelay(true)
+            .build()
+            .map_err(|e| anyhow!("Failed to set up the HTTP client: {}", e))?;
+        Ok(self.client.get_or_init(|| client))
+    }
+
+    /// Whether a request failed because the access token was rejected
+    pub fn token_rejected(&self) -> bool {
+        self.token_rejected.load(Ordering::Relaxed)
+    }
+
+    pub fn set_token(&self, token: OAuthToken) {
+        *self.token.write().unwrap() = Some(token);
+        self.token_rejected.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether the current token is close to expiring and can be refreshed
+    pub fn token_needs_refresh(&self) -> bool {
+        self.token.read().unwrap().as_ref().is_some_and(|token| {
+            token.refresh_token.is_some() && token.expires_within(refresh_margin())
+        })
+    }
+
+    /// Refresh the access token now and persist it to the configuration
+    pub async fn refresh_access_token(&self, config: &mut Config) -> Result<()> {
+        let refresh_token = self
+            .token
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|token| token.refresh_token.clone())
+            .ok_or_else(|| anyhow!("No refresh token available"))?;
+
+        let token = request_token_refresh(
+            self.http()?,
+            &self.client_id,
+            &self.client_secret,
+            &refresh_token,
+        )
+        .await?;
+        config.store_oauth_token(&token)?;
+        self.set_token(token);
+        Ok(())
+    }
+
+    /// Refresh the token in the background whenever it gets close to expiring, so commands
+    /// that run for a while, e.g. `capture` or one waiting at a prompt, outlive it. Requests
+    /// keep using the current token until the new one is swapped in, and the new one is
+    /// stored in the configuration.
+    pub fn refresh_token_in_background(&self) -> Result<TokenRefresh> {
+        let http = self.http()?.clone();
+        let client_id = self.client_id.clone();
+        let client_secret = self.client_secret.clone();
+
+        Ok(spawn_token_refresh(
+            Arc::clone(&self.token),
+            TOKEN_CHECK_INTERVAL,
+            move |refresh_token| {
+                let (http, client_id, client_secret) =
+                    (http.clone(), client_id.clone(), client_secret.clone());
+                async move {
+                    let token =
+                        request_token_refresh(&http, &client_id, &client_secret, &refresh_token)
+                            .await?;
+                    // Loaded again so changes made to the file while the command ran are kept
+                    if let Err(e) =
+                        Config::load().and_then(|mut config| config.store_oauth_token(&token))
+                    {
+                        eprintln!("⚠️ Failed to save refreshed access token: {}", e);
+                    }
+                    Ok(token)
+                }
+            },
+        ))
+    }
+
+    /// How long idle connections stay open for reuse; zero closes them after each request
+    pub fn set_pool_idle_timeout(&mut self, timeout: Duration) {
+        self.pool_idle_timeout = timeout;
+    }
+
+    /// Apply changes without checking whether the task changed on the server in the meantime
+    pub fn set_force(&mut self, force: bool) {
+        self.force = force;
+    }
+
+    /// Fail on the first unparseable item instead of skipping it
+    pub fn set_strict_parse(&mut self, strict_parse: bool) {
+        self.strict_parse = strict_parse;
+    }
+
+    pub fn take_parse_report(&self) -> ParseReport {
+        std::mem::take(&mut *self.parse_report.lock().unwrap())
+    }
+
+    /// Deserialize items one at a time so a single malformed item doesn't fail the whole response
+    fn parse_items<T>(&self, values: Vec<Value>, kind: &str) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        let mut report = self.parse_report.lock().unwrap();
+        let mut items = Vec::with_capacity(values.len());
+
+        for value in values {
+            match T::deserialize(&value) {
+                Ok(item) => {
+                    // Anything that doesn't survive a round trip is a field we don't model
+                    if let (Value::Object(raw), Ok(Value::Object(known))) =
+                        (&value, serde_json::to_value(&item))
+                    {
+                        report.unknown_fields.extend(
+                            raw.keys()
+                                .filter(|key| !known.contains_key(*key))
+                                .map(|key| format!("{}.{}", kind, key)),
+                        );
+                    }
+                    items.push(item);
+                }
+                Err(e) => {
+                    if self.strict_parse {
+                        return Err(anyhow!(
+                            "Failed to parse {}: {}\n{}",
+                            kind,
+                            e,
+                            serde_json::to_string_pretty(&value).unwrap_or_default()
+                        ));
+                    }
+                    report.skipped += 1;
+                }
+            }
         }
+
+        Ok(items)
+    }
+
+    /// Limit the number of HTTP requests this client may issue
+    pub fn set_request_budget(&mut self, max: usize) {
+        self.budget = Some(RequestBudget {
+            max,
+            used: AtomicUsize::new(0),
+        });
+    }
+
+    pub fn request_limit(&self) -> Option<usize> {
+        self.budget.as_ref().map(|budget| budget.max)
+    }
+
+    pub fn remaining_requests(&self) -> Option<usize> {
+        self.budget.as_ref().map(|budget| budget.remaining())
+    }
+
+    /// Only fetch the named projects when syncing or looking for today's tasks
+    pub fn set_project_scope(&mut self, names: &[String]) {
+        self.project_scope = Some(
+            names
+                .iter()
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        );
+    }
+
+    /// Whether syncs and today's view fetch the project with this name; "inbox" is the inbox
+    pub fn project_in_scope(&self, name: &str) -> bool {
+        self.project_scope
+            .as_ref()
+            .is_none_or(|scope| scope.contains(&name.to_lowercase()))
+    }
+
+    fn inbox_in_scope(&self) -> bool {
+        self.project_in_scope("inbox")
+    }
+
+    /// Projects named by the project scope, resolved against the cached project list. The
+    /// list is only fetched again when the cache doesn't know every name.
+    async fn scoped_projects(&self, cache: &mut Cache, scope: &[String]) -> Result<Vec<Project>> {
+        let unknown = |projects: &[Project]| -> Vec<String> {
+            scope
+                .iter()
+                .filter(|name| *name != "inbox")
+                .filter(|name| !projects.iter().any(|p| p.name.to_lowercase() == **name))
+                .cloned()
+                .collect()
+        };
+
+        if cache.projects.is_empty() || !unknown(&cache.projects).is_empty() {
+            let projects = self.get_projects().await?;
+            cache.set_projects(&projects);
+        }
+        let unknown = unknown(&cache.projects);
+        if !unknown.is_empty() {
+            return Err(anyhow!(
+                "Unknown project(s) in the project scope: {}",
+                unknown.join(", ")
+            ));
+        }
+
+        Ok(cache
+            .projects
+            .iter()
+            .filter(|project| scope.contains(&project.name.to_lowercase()))
+            .cloned()
+            .collect())
     }
 
     // Debug helper to log HTTP requests and responses
-    async fn debug_request(&self, request: RequestBuilder, _description: &str) -> Result<Response> {
+    async fn debug_request(&self, request: RequestBuilder, description: &str) -> Result<Response> {
+        if let Some(budget) = &self.budget {
+            if !budget.try_acquire() {
+                return Err(anyhow!("Request budget of {} exhausted", budget.max));
+            }
+        }
+
         let request = request.build()?;
 
         // println!("🔗 HTTP {} {}", request.method(), request.url());
@@ -60,7 +465,17 @@ impl TickTickClient {
 
         // println!("   📤 Sending {} request...", description);
 
-        let response = self.client.execute(request).await?;
+        let started = Instant::now();
+        let response = self.http()?.execute(request).await;
+        let status = response
+            .as_ref()
+            .ok()
+            .map(|response| response.status().as_u16());
+        METRICS.record_request(description, status, started.elapsed());
+        let response = response?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.token_rejected.store(true, Ordering::Relaxed);
+        }
 
         // println!(
         //     "   📥 Response: {} {}",
@@ -80,7 +495,7 @@ impl TickTickClient {
         let mut url = Url::parse("https://ticktick.com/oauth/authorize").unwrap();
         url.query_pairs_mut()
             .append_pair("client_id", &self.client_id)
-            .append_pair("scope", "tasks:read")
+            .append_pair("scope", "tasks:read tasks:write")
             .append_pair("state", state)
             .append_pair("redirect_uri", &self.redirect_uri)
             .append_pair("response_type", "code");
@@ -96,12 +511,12 @@ impl TickTickClient {
             ("client_secret", self.client_secret.as_str()),
             ("code", code),
             ("grant_type", "authorization_code"),
-            ("scope", "tasks:read"),
+            ("scope", "tasks:read tasks:write"),
             ("redirect_uri", self.redirect_uri.as_str()),
         ];
 
         let request = self
-            .client
+            .http()?
             .post("https://ticktick.com/oauth/token")
             .header(header::AUTHORIZATION, format!("Basic {}", auth_header))
             .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
@@ -114,11 +529,11 @@ impl TickTickClient {
             // println!("   📥 Response body: {}", response_text);
 
             let token_response: TokenResponse = serde_json::from_str(&response_text)?;
-            self.access_token = Some(token_response.access_token.clone());
+            let token = OAuthToken::from(token_response);
 
-            // Save token to config file
-            config.ticktick.access_token = Some(token_response.access_token);
-            config.save()?;
+            // Save token to the configured storage
+            config.store_oauth_token(&token)?;
+            self.set_token(token);
 
             println!("✅ Successfully obtained and saved access token!");
             Ok(())
@@ -130,19 +545,29 @@ impl TickTickClient {
     }
 
     fn get_auth_header(&self) -> Result<String> {
-        match &self.access_token {
-            Some(token) => Ok(format!("Bearer {}", token)),
+        match self.token.read().unwrap().as_ref() {
+            Some(token) => Ok(format!("Bearer {}", token.access_token)),
             None => Err(anyhow!(
                 "No access token available. Please authenticate first."
             )),
         }
     }
 
+    /// Whether the API accepts the current token, checked with the project list request
+    pub async fn verify_token(&self) -> Result<bool> {
+        let request = self
+            .http()?
+            .get("https://api.ticktick.com/open/v1/project")
+            .header(header::AUTHORIZATION, self.get_auth_header()?);
+        let response = self.debug_request(request, "Verify token").await?;
+        Ok(response.status() != StatusCode::UNAUTHORIZED)
+    }
+
     pub async fn get_projects(&self) -> Result<Vec<Project>> {
         let auth_header = self.get_auth_header()?;
 
         let request = self
-            .client
+            .http()?
             .get("https://api.ticktick.com/open/v1/project")
             .header(header::AUTHORIZATION, auth_header);
 
@@ -152,8 +577,8 @@ impl TickTickClient {
             let response_text = response.text().await?;
             // println!("   📥 Response body: {}", response_text);
 
-            let projects: Vec<Project> = serde_json::from_str(&response_text)?;
-            Ok(projects)
+            let values: Vec<Value> = serde_json::from_str(&response_text)?;
+            self.parse_items(values, "project")
         } else {
             let error_text = response.text().await?;
             // println!("   📥 Error response body: {}", error_text);
@@ -161,6 +586,64 @@ impl TickTickClient {
         }
     }
 
+    /// Every open task in every project, inbox included, with a single request to the task
+    /// filter endpoint. None when the API doesn't offer it; that is remembered in the cache so
+    /// the per-project fetch is used right away for the next few days.
+    async fn get_all_open_tasks(&self, cache: &mut Cache) -> Result<Option<Vec<Task>>> {
+        let recheck = chrono::Duration::days(TASK_FILTER_RECHECK_DAYS);
+        if cache
+            .task_filter_missing_at
+            .is_some_and(|missing_at| Local::now() - missing_at < recheck)
+        {
+            return Ok(None);
+        }
+
+        let auth_header = self.get_auth_header()?;
+        let request = self
+            .http()?
+            .post("https://api.ticktick.com/open/v1/task/filter")
+            .header(header::AUTHORIZATION, auth_header)
+            .json(&serde_json::json!({ "status": [0] }));
+        let response = self.debug_request(request, "Filter tasks").await?;
+
+        match response.status() {
+            status if status.is_success() => {
+                let response_text = response.text().await?;
+                let values: Vec<Value> = serde_json::from_str(&response_text)?;
+                cache.set_task_filter_missing(None);
+                Ok(Some(self.parse_items(values, "task")?))
+            }
+            // An API without the endpoint. A 400 is a problem with this request, not a missing
+            // endpoint, so it falls through to an error and the endpoint is tried again next time.
+            StatusCode::NOT_FOUND
+            | StatusCode::METHOD_NOT_ALLOWED
+            | StatusCode::NOT_IMPLEMENTED => {
+                cache.set_task_filter_missing(Some(Local::now()));
+                Ok(None)
+            }
+            _ => {
+                let error_text = response.text().await?;
+                Err(anyhow!("Failed to filter tasks: {}", error_text))
+            }
+        }
+    }
+
+    /// Fill the cache with every open task in one request when the API allows it, falling
+    /// back to one request per project otherwise. Returns false when nothing was fetched.
+    async fn fetch_all_at_once(&self, cache: &mut Cache, projects: &[Project]) -> bool {
+        match self.get_all_open_tasks(cache).await {
+            Ok(Some(tasks)) => {
+                cache.set_all_tasks(projects, &tasks);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                eprintln!("⚠️ {}, fetching project by project instead", e);
+                false
+            }
+        }
+    }
+
     async fn get_project_data(&self, project_id: &str) -> Result<ProjectData> {
         let auth_header = self.get_auth_header()?;
 
@@ -169,19 +652,20 @@ impl TickTickClient {
             project_id
         );
         let request = self
-            .client
+            .http()?
             .get(&url)
             .header(header::AUTHORIZATION, auth_header);
 
-        let response = self
-            .debug_request(request, &format!("Get project data for {}", project_id))
-            .await?;
+        let response = self.debug_request(request, "Get project data").await?;
 
         if response.status().is_success() {
             let response_text = response.text().await?;
             // println!("   📥 Response body: {}", response_text);
 
-            let project_data: ProjectData = serde_json::from_str(&response_text)?;
+            let mut value: Value = serde_json::from_str(&response_text)?;
+            let tasks = take_array(&mut value, "tasks");
+            let mut project_data = ProjectData::deserialize(&value)?;
+            project_data.tasks = self.parse_items(tasks, "task")?;
             Ok(project_data)
         } else {
             let error_text = response.text().await?;
@@ -190,64 +674,30 @@ impl TickTickClient {
         }
     }
 
-    fn is_task_due_today(&self, task: &Task) -> bool {
-        let today = Local::now().date_naive();
-
-        // Check due date
-        if let Some(due_date_str) = &task.due_date {
-            if let Ok((due_date, _)) = parse(due_date_str) {
-                // dtparse returns a NaiveDateTime, so we need to assume it's in local timezone
-                let due_date_local = Local
-                    .from_local_datetime(&due_date)
-                    .single()
-                    .map(|dt| dt.date_naive())
-                    .unwrap_or_else(|| due_date.date());
-
-                if due_date_local == today {
-                    return true;
-                }
-            }
-        }
-
-        // Check start date for today
-        if let Some(start_date_str) = &task.start_date {
-            if let Ok((start_date, _)) = parse(start_date_str) {
-                // dtparse returns a NaiveDateTime, so we need to assume it's in local timezone
-                let start_date_local = Local
-                    .from_local_datetime(&start_date)
-                    .single()
-                    .map(|dt| dt.date_naive())
-                    .unwrap_or_else(|| start_date.date());
-
-                if start_date_local == today {
-                    return true;
-                }
-            }
-        }
-
-        false
-    }
+    pub async fn get_task(&self, project_id: &str, task_id: &str) -> Result<Task> {
+        let auth_header = self.get_auth_header()?;
 
-    fn is_task_overdue(&self, task: &Task) -> bool {
-        let today = Local::now().date_naive();
+        let url = format!(
+            "https://api.ticktick.com/open/v1/project/{}/task/{}",
+            project_id, task_id
+        );
+        let request = self
+            .http()?
+            .get(&url)
+            .header(header::AUTHORIZATION, auth_header);
 
-        // Check due date
-        if let Some(due_date_str) = &task.due_date {
-            if let Ok((due_date, _)) = parse(due_date_str) {
-                // dtparse returns a NaiveDateTime, so we need to assume it's in local timezone
-                let due_date_local = Local
-                    .from_local_datetime(&due_date)
-                    .single()
-                    .map(|dt| dt.date_naive())
-                    .unwrap_or_else(|| due_date.date());
+        let response = self.debug_request(request, "Get task").await?;
 
-                if due_date_local < today {
-                    return true;
-                }
-            }
+        if response.status().is_success() {
+            let response_text = response.text().await?;
+            let value: Value = serde_json::from_str(&response_text)?;
+            self.parse_items(vec![value], "task")?
+                .pop()
+                .ok_or_else(|| anyhow!("Task {} could not be parsed", task_id))
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow!("Failed to get task: {}", error_text))
         }
-
-        false
     }
 
     async fn get_inbox_data(&self) -> Result<InboxData> {
@@ -255,7 +705,7 @@ impl TickTickClient {
 
         let url = "https://api.ticktick.com/open/v1/project/inbox/data";
         let request = self
-            .client
+            .http()?
             .get(url)
             .header(header::AUTHORIZATION, auth_header);
 
@@ -265,7 +715,10 @@ impl TickTickClient {
             let response_text = response.text().await?;
             // println!("   📥 Inbox response body: {}", response_text);
 
-            let inbox_data: InboxData = serde_json::from_str(&response_text)?;
+            let mut value: Value = serde_json::from_str(&response_text)?;
+            let tasks = take_array(&mut value, "tasks");
+            let mut inbox_data = InboxData::deserialize(&value)?;
+            inbox_data.tasks = self.parse_items(tasks, "task")?;
             Ok(inbox_data)
         } else {
             let error_text = response.text().await?;
@@ -274,28 +727,329 @@ impl TickTickClient {
         }
     }
 
-    pub async fn get_todays_tasks(&self) -> Result<Vec<Task>> {
-        let projects = self.get_projects().await?;
+    /// Wait for earlier changes to the same task, then make sure nobody else changed it since
+    /// `task` was fetched. The returned guard keeps the task's place in the queue until the
+    /// change has been sent.
+    ///
+    /// The queue only orders changes within this process. The check against other writers
+    /// costs a GET per change, counted by `--max-requests`, and can still race with a change
+    /// made between that GET and the write.
+    async fn queue_write(&self, task: &Task) -> Result<tokio::sync::OwnedMutexGuard<()>> {
+        let lock = self
+            .write_queue
+            .lock()
+            .unwrap()
+            .entry(task.id.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock_owned().await;
+
+        if self.force {
+            return Ok(guard);
+        }
+        let Some(seen) = task.modified_time.as_deref() else {
+            return Ok(guard);
+        };
+        let current = self.get_task(&task.project_id, &task.id).await?;
+        match current.modified_time.as_deref() {
+            Some(modified) if modified != seen => Err(anyhow!(
+                "Conflict: \"{}\" was changed elsewhere ({}) after tick fetched it ({}). Sync \
+                 (e.g. `tick list`) and try again, or pass --force to overwrite it.",
+                task.title,
+                modified,
+                seen
+            )),
+            _ => Ok(guard),
+        }
+    }
+
+    /// Send a mutating request and record it in the audit log
+    async fn send_mutation(
+        &self,
+        request: RequestBuilder,
+        action: AuditAction,
+        project_id: Option<&str>,
+        task_id: Option<&str>,
+        summary: &str,
+    ) -> Result<Response> {
+        let result = self
+            .debug_request(request, &format!("{} task", action))
+            .await;
+
+        let entry = AuditEntry {
+            timestamp: Local::now(),
+            action,
+            project_id: project_id.map(str::to_string),
+            task_id: task_id.map(str::to_string),
+            summary: summary.to_string(),
+            // 0 marks a request that never got a response
+            status: result
+                .as_ref()
+                .map(|response| response.status().as_u16())
+                .unwrap_or(0),
+        };
+        if let Err(e) = audit::record(&entry) {
+            eprintln!("⚠️ Failed to write audit log: {}", e);
+        }
+
+        result
+    }
+
+    pub async fn create_task(&self, task: &NewTask) -> Result<Task> {
+        let auth_header = self.get_auth_header()?;
+
+        let request = self
+            .http()?
+            .post("https://api.ticktick.com/open/v1/task")
+            .header(header::AUTHORIZATION, auth_header)
+            .json(task);
+
+        let response = self
+            .send_mutation(
+                request,
+                AuditAction::Create,
+                task.project_id.as_deref(),
+                None,
+                &task.title,
+            )
+            .await?;
+
+        if response.status().is_success() {
+            let response_text = response.text().await?;
+            Ok(serde_json::from_str(&response_text)?)
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow!("Failed to create task: {}", error_text))
+        }
+    }
+
+    pub async fn update_task(&self, task: &Task) -> Result<Task> {
+        let _queued = self.queue_write(task).await?;
+        let auth_header = self.get_auth_header()?;
+
+        let url = format!("https://api.ticktick.com/open/v1/task/{}", task.id);
+        let request = self
+            .http()?
+            .post(&url)
+            .header(header::AUTHORIZATION, auth_header)
+            .json(task);
+
+        let response = self
+            .send_mutation(
+                request,
+                AuditAction::Update,
+                Some(&task.project_id),
+                Some(&task.id),
+                &task.title,
+            )
+            .await?;
+
+        if response.status().is_success() {
+            let response_text = response.text().await?;
+            Ok(serde_json::from_str(&response_text)?)
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow!("Failed to update task: {}", error_text))
+        }
+    }
+
+    /// Not queued or checked for conflicts: completing doesn't send the task's fields, so it
+    /// can't overwrite a change made elsewhere
+    pub async fn complete_task(&self, task: &Task) -> Result<()> {
+        let auth_header = self.get_auth_header()?;
+
+        let url = format!(
+            "https://api.ticktick.com/open/v1/project/{}/task/{}/complete",
+            task.project_id, task.id
+        );
+        let request = self
+            .http()?
+            .post(&url)
+            .header(header::AUTHORIZATION, auth_header);
+
+        let response = self
+            .send_mutation(
+                request,
+                AuditAction::Complete,
+                Some(&task.project_id),
+                Some(&task.id),
+                &task.title,
+            )
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow!("Failed to complete task: {}", error_text))
+        }
+    }
+
+    pub async fn delete_task(&self, task: &Task) -> Result<()> {
+        let _queued = self.queue_write(task).await?;
+        let auth_header = self.get_auth_header()?;
+
+        let url = format!(
+            "https://api.ticktick.com/open/v1/project/{}/task/{}",
+            task.project_id, task.id
+        );
+        let request = self
+            .http()?
+            .delete(&url)
+            .header(header::AUTHORIZATION, auth_header);
+
+        let response = self
+            .send_mutation(
+                request,
+                AuditAction::Delete,
+                Some(&task.project_id),
+                Some(&task.id),
+                &task.title,
+            )
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let error_text = response.text().await?;
+            Err(anyhow!("Failed to delete task: {}", error_text))
+        }
+    }
+
+    /// Fetch every project (or every project in scope) and its open tasks into the cache,
+    /// returning previously cached tasks that are no longer open anywhere
+    pub async fn sync(&self, cache: &mut Cache) -> Result<Vec<Task>> {
+        let previous: Vec<Task> = cache.tasks.values().flatten().cloned().collect();
+
+        let projects = match &self.project_scope {
+            Some(scope) => self.scoped_projects(cache, scope).await?,
+            None => {
+                let projects = self.get_projects().await?;
+                cache.set_projects(&projects);
+                projects
+            }
+        };
+
+        // A scope names only a few projects, which are cheaper to fetch one by one than
+        // every task at once
+        let fetched =
+            self.project_scope.is_none() && self.fetch_all_at_once(cache, &projects).await;
+        if !fetched {
+            if self.inbox_in_scope() {
+                let inbox_data = self.get_inbox_data().await?;
+                cache.set_project_tasks("inbox", &inbox_data.tasks);
+            }
+
+            for project in &projects {
+                let project_data = self.get_project_data(&project.id).await?;
+                cache.set_project_tasks(&project.id, &project_data.tasks);
+            }
+        }
+
+        // Drop lists for projects that no longer exist so their tasks count as closed. Out of
+        // scope projects weren't fetched, so their lists are kept as they were.
+        if self.project_scope.is_none() {
+            cache
+                .tasks
+                .retain(|id, _| id == "inbox" || projects.iter().any(|project| &project.id == id));
+        }
+
+        Ok(previous
+            .into_iter()
+            .filter(|task| cache.find_task(&task.id).ok().flatten().is_none())
+            .collect())
+    }
+
+    /// The tasks among `vanished` (open before a sync, gone after it) that were completed
+    /// rather than deleted, asking the API about each one. Tasks that can't be checked, e.g.
+    /// once the request budget runs out, are left out too.
+    pub async fn completed_among(&self, vanished: Vec<Task>) -> Vec<Task> {
+        let mut completed = Vec::new();
+        for task in vanished {
+            if self.remaining_requests() == Some(0) {
+                break;
+            }
+            // A deleted task can't be fetched any more
+            if let Ok(current) = self.get_task(&task.project_id, &task.id).await {
+                if current.status != 0 {
+                    completed.push(current);
+                }
+            }
+        }
+        completed
+    }
+
+    fn is_task_actionable(&self, task: &Task) -> bool {
+        let today = DateWindow::day(Local::now().date_naive());
+        TodayCheck::of_task(task, today).shown()
+    }
+
+    pub async fn get_todays_tasks(&self, cache: &mut Cache) -> Result<TodaysTasks> {
+        // With a request budget, prefer the cached project list over a fresh fetch. Without
+        // one and no budget left, only the inbox is known.
+        let projects_unknown = self.remaining_requests() == Some(0) && cache.projects.is_empty();
+        let mut projects = if projects_unknown {
+            Vec::new()
+        } else if let Some(scope) = &self.project_scope {
+            self.scoped_projects(cache, scope).await?
+        } else if self.budget.is_some() && !cache.projects.is_empty() {
+            println!("📦 Using cached project list");
+            cache.projects.clone()
+        } else {
+            let projects = self.get_projects().await?;
+            cache.set_projects(&projects);
+            projects
+        };
         // dbg!(&projects);
+        if self.project_scope.is_none()
+            && self.remaining_requests() != Some(0)
+            && self.fetch_all_at_once(cache, &projects).await
+        {
+            println!("📋 Fetched the open tasks of all projects at once");
+            let tasks = cache
+                .tasks
+                .values()
+                .flatten()
+                .filter(|task| self.is_task_actionable(task))
+                .cloned()
+                .collect();
+            return Ok(TodaysTasks {
+                tasks,
+                projects,
+                skipped_projects: Vec::new(),
+                projects_unknown: false,
+            });
+        }
+
         let mut todays_tasks = Vec::new();
+        let mut skipped_projects = Vec::new();
 
-        // Check inbox first
-        println!("📥 Checking inbox for today's tasks...");
-        match self.get_inbox_data().await {
-            Ok(inbox_data) => {
-                println!("✅ Successfully fetched inbox data");
-                for task in inbox_data.tasks {
-                    // Only include uncompleted tasks (status 0 = Normal)
-                    if task.status == 0
-                        && (self.is_task_due_today(&task) || self.is_task_overdue(&task))
-                    {
-                        todays_tasks.push(task);
-                    }
+        // Check inbox first, unless the project scope leaves it out
+        let check_inbox = self.inbox_in_scope();
+        if check_inbox && self.remaining_requests() == Some(0) {
+            skipped_projects.push("Inbox".to_string());
+        } else if check_inbox {
+            println!("📥 Checking inbox for today's tasks...");
+            match self.get_inbox_data().await {
+                Ok(inbox_data) => {
+                    println!("✅ Successfully fetched inbox data");
+                    cache.set_project_tasks("inbox", &inbox_data.tasks);
+                    todays_tasks.extend(
+                        inbox_data
+                            .tasks
+                            .into_iter()
+                            .filter(|task| self.is_task_actionable(task)),
+                    );
+                }
+                Err(e) => {
+                    println!("❌ Failed to fetch inbox data: {}", e);
                 }
             }
-            Err(e) => {
-                println!("❌ Failed to fetch inbox data: {}", e);
-            }
+        }
+
+        // Spend a limited budget on the most recently active projects first
+        if self.budget.is_some() {
+            projects.sort_by_key(|project| std::cmp::Reverse(cache.last_activity(&project.id)));
         }
 
         println!(
@@ -303,19 +1057,23 @@ impl TickTickClient {
             projects.len()
         );
 
-        for project in projects {
+        for project in &projects {
+            if self.remaining_requests() == Some(0) {
+                skipped_projects.push(project.name.clone());
+                continue;
+            }
+
             println!("  🔍 Checking project: {}", project.name);
 
             match self.get_project_data(&project.id).await {
                 Ok(project_data) => {
-                    for task in project_data.tasks {
-                        // Only include uncompleted tasks (status 0 = Normal)
-                        if task.status == 0
-                            && (self.is_task_due_today(&task) || self.is_task_overdue(&task))
-                        {
-                            todays_tasks.push(task);
-                        }
-                    }
+                    cache.set_project_tasks(&project.id, &project_data.tasks);
+                    todays_tasks.extend(
+                        project_data
+                            .tasks
+                            .into_iter()
+                            .filter(|task| self.is_task_actionable(task)),
+                    );
                 }
                 Err(e) => {
                     println!(
@@ -326,6 +1084,93 @@ impl TickTickClient {
             }
         }
 
-        Ok(todays_tasks)
+        Ok(TodaysTasks {
+            tasks: todays_tasks,
+            projects,
+            skipped_projects,
+            projects_unknown,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn token(access_token: &str, expires_in: chrono::Duration) -> OAuthToken {
+        OAuthToken {
+            access_token: access_token.to_string(),
+            refresh_token: Some(format!("{}-refresh", access_token)),
+            expires_at: Some(Utc::now() + expires_in),
+        }
+    }
+
+    fn access_token(token: &RwLock<Option<OAuthToken>>) -> String {
+        token.read().unwrap().as_ref().unwrap().access_token.clone()
+    }
+
+    /// Background refresh that hands out a token "new" and counts how often it's asked to
+    fn refresh_with(
+        shared: &Arc<RwLock<Option<OAuthToken>>>,
+        refreshes: &Arc<AtomicUsize>,
+    ) -> TokenRefresh {
+        let refreshes = Arc::clone(refreshes);
+        spawn_token_refresh(
+            Arc::clone(shared),
+            Duration::from_millis(5),
+            move |refresh_token| {
+                refreshes.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    assert_eq!(refresh_token, "old-refresh");
+                    Ok(token("new", chrono::Duration::hours(1)))
+                }
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn expiring_tokens_are_swapped_once() {
+        let shared = Arc::new(RwLock::new(Some(token(
+            "old",
+            chrono::Duration::minutes(1),
+        ))));
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let _refresh = refresh_with(&shared, &refreshes);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while access_token(&shared) != "new" {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // The new token is far from expiring, so it's left alone
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn fresh_tokens_are_left_alone() {
+        let shared = Arc::new(RwLock::new(Some(token("old", chrono::Duration::hours(1)))));
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let _refresh = refresh_with(&shared, &refreshes);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(refreshes.load(Ordering::SeqCst), 0);
+        assert_eq!(access_token(&shared), "old");
+    }
+
+    #[tokio::test]
+    async fn dropping_the_guard_stops_refreshing() {
+        let shared = Arc::new(RwLock::new(Some(token("old", chrono::Duration::hours(1)))));
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        drop(refresh_with(&shared, &refreshes));
+
+        // Expiring now, but nothing is watching any more
+        *shared.write().unwrap() = Some(token("old", chrono::Duration::minutes(1)));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(refreshes.load(Ordering::SeqCst), 0);
     }
 }
@@ -0,0 +1,67 @@
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::time::Duration;
+
+static TITLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+
+fn read_text() -> Result<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| anyhow!("Failed to read the clipboard: {}", e))
+}
+
+/// Decode the handful of entities common in page titles
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// The page's `<title>`, or None if it can't be fetched or has none
+async fn page_title(url: &url::Url) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .ok()?;
+    let html = client
+        .get(url.as_str())
+        .send()
+        .await
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+    let title = TITLE.captures(&html)?.get(1)?.as_str();
+    let title = decode_entities(&title.split_whitespace().collect::<Vec<_>>().join(" "));
+    (!title.is_empty()).then_some(title)
+}
+
+/// Title and content for a task from the clipboard: the first line is the title and the rest
+/// the content. A lone http(s) URL becomes "Read: <page title>" with the URL as content.
+pub async fn task_text() -> Result<(String, Option<String>)> {
+    let text = read_text()?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(anyhow!("The clipboard is empty"));
+    }
+
+    if let Ok(url) = url::Url::parse(text) {
+        if matches!(url.scheme(), "http" | "https") && !text.contains(char::is_whitespace) {
+            eprintln!("🌐 Fetching page title...");
+            let title = page_title(&url).await.unwrap_or_else(|| text.to_string());
+            return Ok((format!("Read: {}", title), Some(text.to_string())));
+        }
+    }
+
+    let (title, content) = text.split_once('\n').unwrap_or((text, ""));
+    let content = content.trim();
+    Ok((
+        title.trim().to_string(),
+        (!content.is_empty()).then(|| content.to_string()),
+    ))
+}
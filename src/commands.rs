@@ -0,0 +1,2203 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveTime, TimeZone};
+use regex::RegexBuilder;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Read, Write};
+
+use crate::audit;
+use crate::cache::Cache;
+use crate::cli::{
+    AddArgs, BulkAction, BulkArgs, ConfigAction, DigestFormat, DuplicateArgs, EditArgs,
+    GithubSyncArgs, GrepArgs, IcsImportArgs, MailFetchArgs, MqttArgs, ReorderArgs, TodosScanArgs,
+};
+use crate::client::TickTickClient;
+use crate::clipboard;
+use crate::config::{Config, HooksConfig, TokenStorage};
+use crate::crypt;
+use crate::dates::{
+    due_date_warnings, parse_api_datetime, parse_date_shift, parse_user_datetime, shift_datetime,
+    to_api_datetime, DateShift, DateWindow,
+};
+use crate::dedup::{self, OnDuplicate};
+use crate::digest::Digest;
+use crate::display::{
+    self, render_month_heatmap, render_task_details, render_task_simple, render_tasks_grouped,
+    render_week_grid,
+};
+use crate::explain;
+use crate::filter::{Filter, FilterContext};
+use crate::github::{self, GitHub};
+use crate::grep;
+use crate::help;
+use crate::history;
+use crate::hooks;
+use crate::i18n::{self, text};
+use crate::ics::{self, EventTime, IcsEvent};
+use crate::ingest;
+use crate::listing;
+use crate::mail::{self, Mailbox};
+use crate::metrics::METRICS;
+use crate::mqtt::{self, MqttSettings};
+use crate::notify::{self, NotifyTarget};
+use crate::paths;
+use crate::quickadd;
+use crate::reorder::{self, Move};
+use crate::settings;
+use crate::todos;
+use crate::track;
+use crate::types::{NewChecklistItem, NewTask, Priority, Project, Task};
+
+pub async fn run_today(client: &TickTickClient) -> Result<()> {
+    println!();
+    println!("🗓️ {}", text().fetching_today);
+    // println!("🌐 About to make HTTP requests to fetch tasks...");
+    if let Ok(Some(timer)) = track::running() {
+        println!(
+            "⏱️ Tracking \"{}\" for {}",
+            timer.title,
+            track::format_duration(timer.elapsed())
+        );
+    }
+    println!();
+
+    let mut cache = Cache::load();
+    let result = client.get_todays_tasks(&mut cache).await;
+    cache.refresh_dependencies();
+    if let Err(e) = cache.save() {
+        println!("⚠️ {}: {}", text().cache_update_failed, e);
+    }
+
+    match result {
+        Ok(todays) => {
+            let partial = !todays.skipped_projects.is_empty() || todays.projects_unknown;
+            if todays.tasks.is_empty() && !partial {
+                println!("🎉 {}", text().all_caught_up);
+            } else if todays.tasks.is_empty() {
+                println!("🔎 {}", text().none_in_checked_projects);
+            } else {
+                println!("📅 {}", (text().tasks_for_today)(todays.tasks.len()));
+                println!();
+                render_listing("today", &todays.tasks, &todays.projects, &cache.blocked_by)?;
+            }
+
+            let limit = client.request_limit().unwrap_or(0);
+            if todays.projects_unknown {
+                println!();
+                println!("⚠️ {}", (text().project_list_skipped)(limit));
+            }
+            if !todays.skipped_projects.is_empty() {
+                println!();
+                println!(
+                    "⚠️ {}",
+                    (text().budget_exhausted)(
+                        limit,
+                        todays.skipped_projects.len(),
+                        &todays.skipped_projects.join(", ")
+                    )
+                );
+            }
+            if partial {
+                return Err(anyhow!(text().partial_results));
+            }
+        }
+        Err(e) => {
+            println!("❌ {}: {}", text().fetch_failed, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print tasks grouped by project, and remember how they were numbered so later commands
+/// can refer to them by position
+fn render_listing(
+    command: &str,
+    tasks: &[Task],
+    projects: &[Project],
+    blocked_by: &HashMap<String, Vec<String>>,
+) -> Result<()> {
+    let mut shown = Vec::new();
+    display::to_stdout(|out| {
+        shown = render_tasks_grouped(out, tasks, projects, blocked_by)?;
+        Ok(())
+    })?;
+
+    if let Err(e) = listing::save(command, shown) {
+        eprintln!("⚠️ Failed to remember the listed tasks: {}", e);
+    }
+    Ok(())
+}
+
+/// Look up a task by id, id prefix or position in the last listing, syncing the cache if it
+/// isn't known yet
+async fn resolve_task(client: &TickTickClient, cache: &mut Cache, id: &str) -> Result<Task> {
+    let ids = listing::expand(id)?;
+    let [id] = ids.as_slice() else {
+        return Err(anyhow!(
+            "'{}' refers to {} tasks, but this command takes one",
+            id,
+            ids.len()
+        ));
+    };
+    find_task(client, cache, id).await
+}
+
+/// Look up a task by id or id prefix, syncing if it isn't cached
+async fn find_task(client: &TickTickClient, cache: &mut Cache, id: &str) -> Result<Task> {
+    let cached = cache.find_task(id)?.cloned();
+    METRICS.record_cache_lookup(cached.is_some());
+    if let Some(task) = cached {
+        return Ok(task);
+    }
+
+    println!("🔄 Task not found in cache, syncing...");
+    client.sync(cache).await?;
+    cache
+        .find_task(id)?
+        .cloned()
+        .ok_or_else(|| anyhow!("No open task found with id '{}'", id))
+}
+
+/// Look up a project by name or id, fetching the project list if it isn't cached
+async fn resolve_project(
+    client: &TickTickClient,
+    cache: &mut Cache,
+    name: &str,
+) -> Result<Project> {
+    let cached = cache.find_project(name).is_some();
+    METRICS.record_cache_lookup(cached);
+    if !cached {
+        let projects = client.get_projects().await?;
+        cache.set_projects(&projects);
+    }
+
+    cache
+        .find_project(name)
+        .cloned()
+        .ok_or_else(|| anyhow!("No project named '{}'", name))
+}
+
+/// Fetch every project's open tasks into the cache, recording tasks completed since the last
+/// sync
+async fn synced_cache(client: &TickTickClient) -> Result<Cache> {
+    eprintln!("🔄 {}", text().syncing);
+    let mut cache = Cache::load();
+    let closed = client.sync(&mut cache).await?;
+    cache.refresh_dependencies();
+    cache.save()?;
+
+    let completed = client.completed_among(closed).await;
+    if let Err(e) = history::record_completions(&completed, true) {
+        eprintln!("⚠️ Failed to update history: {}", e);
+    }
+    Ok(cache)
+}
+
+/// Cached tasks matching a filter, or every cached task without one
+fn matching_tasks(cache: &Cache, filter: Option<&Filter>) -> Vec<Task> {
+    let context = FilterContext::new(&cache.projects);
+    cache
+        .tasks
+        .values()
+        .flatten()
+        .filter(|task| filter.is_none_or(|filter| filter.matches(task, &context)))
+        .cloned()
+        .collect()
+}
+
+/// Ask a yes/no question, defaulting to no
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} (y/N): ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Fill in due date fields from a user-supplied date
+fn apply_due_date(due: &str) -> Result<(String, bool)> {
+    let (due_date, is_all_day) = parse_user_datetime(due)?;
+    Ok((to_api_datetime(due_date), is_all_day))
+}
+
+/// Parse a due date given to add or edit. Unless `yes` is set, a date that looks like a
+/// mistake is shown in full and only used once confirmed. Turning it down, or having no
+/// terminal to ask on, is an error, so scripts don't mistake it for success.
+fn checked_due_date(due: &str, yes: bool) -> Result<(String, bool)> {
+    let (due_date, is_all_day) = parse_user_datetime(due)?;
+    let warnings = due_date_warnings(due, due_date, is_all_day, Local::now());
+
+    if !yes && !warnings.is_empty() {
+        let resolved = if is_all_day {
+            due_date.format("%A %Y-%m-%d, all day").to_string()
+        } else {
+            due_date
+                .format("%A %Y-%m-%d %H:%M local time (UTC%:z)")
+                .to_string()
+        };
+        let warning = format!(
+            "\"{}\" means {}, but {}",
+            due,
+            resolved,
+            warnings.join(" and ")
+        );
+        if !io::stdin().is_terminal() {
+            return Err(anyhow!("{}. Pass --yes to use it anyway", warning));
+        }
+        println!("⚠️ {}", warning);
+        if !confirm("Use this due date?")? {
+            return Err(anyhow!("Cancelled, nothing was changed"));
+        }
+    }
+    Ok((to_api_datetime(due_date), is_all_day))
+}
+
+pub async fn run_add(client: &TickTickClient, args: AddArgs) -> Result<()> {
+    if args.json_stdin {
+        return run_add_json(client, args.project.as_deref(), &args.duplicates).await;
+    }
+    let mut cache = Cache::load();
+
+    let project_id = match &args.project {
+        Some(name) => Some(resolve_project(client, &mut cache, name).await?.id),
+        None => None,
+    };
+
+    let (title, content) = if args.from_clipboard {
+        clipboard::task_text().await?
+    } else {
+        (args.title.join(" "), args.content)
+    };
+
+    let mut new_task = NewTask {
+        title,
+        project_id,
+        content,
+        priority: args.priority.map(|priority| priority.value()),
+        ..Default::default()
+    };
+    if let Some(due) = &args.due {
+        let (due_date, is_all_day) = checked_due_date(due, args.yes)?;
+        new_task.start_date = Some(due_date.clone());
+        new_task.due_date = Some(due_date);
+        new_task.is_all_day = Some(is_all_day);
+    }
+
+    if let Added::Created(task) =
+        create_unless_duplicate(client, &mut cache, &new_task, &args.duplicates).await?
+    {
+        println!("✅ Created task:");
+        display::to_stdout(|out| render_task_simple(out, &task, &[]))?;
+    }
+
+    cache.save()?;
+    Ok(())
+}
+
+/// What `create_unless_duplicate` did with a new task
+enum Added {
+    Created(Task),
+    /// An existing task was updated instead
+    Updated(Task),
+    /// The existing task with this id was kept as it was
+    Skipped(String),
+}
+
+impl Added {
+    /// Id of the task now standing for the new one
+    fn task_id(&self) -> &str {
+        match self {
+            Added::Created(task) | Added::Updated(task) => &task.id,
+            Added::Skipped(id) => id,
+        }
+    }
+}
+
+/// Ask whether to skip, update or create a task matching `existing`
+fn ask_duplicate(title: &str, existing: &Task) -> Result<OnDuplicate> {
+    print!(
+        "⚠️ \"{}\" matches an existing task (🆔 {}). [s]kip, [u]pdate it, or [c]reate anyway? (S/u/c): ",
+        title, existing.id
+    );
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "u" | "update" => OnDuplicate::Update,
+        "c" | "create" => OnDuplicate::Create,
+        _ => OnDuplicate::Skip,
+    })
+}
+
+/// Create a task, unless a cached open task looks the same and the --on-duplicate policy says
+/// to skip or update it instead
+async fn create_unless_duplicate(
+    client: &TickTickClient,
+    cache: &mut Cache,
+    new_task: &NewTask,
+    duplicates: &DuplicateArgs,
+) -> Result<Added> {
+    let existing = match duplicates.on_duplicate {
+        OnDuplicate::Create => None,
+        _ => dedup::find(cache, new_task, duplicates.match_content).cloned(),
+    };
+    let action = match (&existing, duplicates.on_duplicate) {
+        (None, _) => OnDuplicate::Create,
+        (Some(existing), OnDuplicate::Ask) => ask_duplicate(&new_task.title, existing)?,
+        (Some(_), policy) => policy,
+    };
+
+    match (existing, action) {
+        (Some(existing), OnDuplicate::Skip) => {
+            println!(
+                "⏭️ Skipped \"{}\", it matches 🆔 {}",
+                new_task.title, existing.id
+            );
+            Ok(Added::Skipped(existing.id))
+        }
+        (Some(mut existing), OnDuplicate::Update) => {
+            dedup::merge(&mut existing, new_task);
+            let task = client.update_task(&existing).await?;
+            println!("✏️ Updated existing task: {}  🆔 {}", task.title, task.id);
+            cache.upsert_task(&task);
+            Ok(Added::Updated(task))
+        }
+        _ => {
+            let task = client.create_task(new_task).await?;
+            cache.upsert_task(&task);
+            Ok(Added::Created(task))
+        }
+    }
+}
+
+/// Create the tasks in a JSON array on stdin. Every entry is checked before the first task is
+/// created, so a typo doesn't leave half the batch behind.
+async fn run_add_json(
+    client: &TickTickClient,
+    default_project: Option<&str>,
+    duplicates: &DuplicateArgs,
+) -> Result<()> {
+    if duplicates.on_duplicate == OnDuplicate::Ask {
+        return Err(anyhow!(
+            "--on-duplicate ask can't prompt while stdin holds the JSON input"
+        ));
+    }
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let specs = ingest::parse(&input)?;
+    if specs.is_empty() {
+        println!("📭 No tasks in the input");
+        return Ok(());
+    }
+
+    let mut cache = Cache::load();
+    let mut new_tasks = Vec::new();
+    for (index, spec) in specs.into_iter().enumerate() {
+        let project_id = match spec.project.as_deref().or(default_project) {
+            Some(name) => Some(resolve_project(client, &mut cache, name).await?.id),
+            None => None,
+        };
+        let mut new_task = NewTask {
+            title: spec.title,
+            project_id,
+            content: spec.content,
+            priority: spec.priority.map(|priority| priority.value()),
+            tags: (!spec.tags.is_empty()).then_some(spec.tags),
+            items: (!spec.checklist.is_empty()).then(|| {
+                spec.checklist
+                    .into_iter()
+                    .map(|title| NewChecklistItem { title })
+                    .collect()
+            }),
+            ..Default::default()
+        };
+        if let Some(due) = &spec.due {
+            let (due_date, is_all_day) = apply_due_date(due)
+                .map_err(|e| anyhow!("Task {} in the input: {}", index + 1, e))?;
+            new_task.start_date = Some(due_date.clone());
+            new_task.due_date = Some(due_date);
+            new_task.is_all_day = Some(is_all_day);
+        }
+        new_tasks.push(new_task);
+    }
+
+    let mut created = 0;
+    let mut failed = 0;
+    for new_task in &new_tasks {
+        match create_unless_duplicate(client, &mut cache, new_task, duplicates).await {
+            Ok(Added::Created(task)) => {
+                println!("  ✅ {}  🆔 {}", task.title, task.id);
+                created += 1;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                failed += 1;
+                println!("  ❌ {}: {}", new_task.title, e);
+            }
+        }
+    }
+    cache.save()?;
+
+    println!(
+        "✅ Created {} of {} task(s), {} matched existing tasks",
+        created,
+        new_tasks.len(),
+        new_tasks.len() - created - failed
+    );
+    if failed > 0 {
+        return Err(anyhow!("{} task(s) could not be created", failed));
+    }
+    Ok(())
+}
+
+/// Due date from a quick-add `@when`: relative amounts of whole days and the words today and
+/// tomorrow give all-day tasks, hours give a time, anything else is parsed as a date
+fn quick_add_due_date(when: &str) -> Result<(String, bool)> {
+    let all_day = |date: NaiveDate| apply_due_date(&date.format("%Y-%m-%d").to_string());
+    match parse_date_shift(when)? {
+        DateShift::By(duration) if duration.num_seconds() % 86_400 == 0 => {
+            all_day(shift_datetime(Local::now(), duration)?.date_naive())
+        }
+        DateShift::By(duration) => Ok((
+            to_api_datetime(shift_datetime(Local::now(), duration)?),
+            false,
+        )),
+        DateShift::To(date) if matches!(when.to_lowercase().as_str(), "today" | "tomorrow") => {
+            all_day(date)
+        }
+        DateShift::To(_) => apply_due_date(when),
+    }
+}
+
+/// Create a task from one line of quick-add syntax
+async fn capture_line(
+    client: &TickTickClient,
+    cache: &mut Cache,
+    line: &str,
+    default_project: Option<&str>,
+) -> Result<Task> {
+    let quick_add = quickadd::parse(line)?;
+
+    let project_id = match quick_add.project.as_deref().or(default_project) {
+        Some(name) => Some(resolve_project(client, cache, name).await?.id),
+        None => None,
+    };
+    let mut new_task = NewTask {
+        title: quick_add.title,
+        project_id,
+        priority: quick_add.priority.map(|priority| priority.value()),
+        tags: (!quick_add.tags.is_empty()).then_some(quick_add.tags),
+        ..Default::default()
+    };
+    if let Some(when) = &quick_add.due {
+        let (due_date, is_all_day) = quick_add_due_date(when)?;
+        new_task.start_date = Some(due_date.clone());
+        new_task.due_date = Some(due_date);
+        new_task.is_all_day = Some(is_all_day);
+    }
+
+    let task = client.create_task(&new_task).await?;
+    cache.upsert_task(&task);
+    Ok(task)
+}
+
+/// Read tasks from stdin one line at a time, creating each as soon as it's entered
+pub async fn run_capture(client: &TickTickClient, project: Option<&str>) -> Result<()> {
+    let mut cache = Cache::load();
+    if let Some(name) = project {
+        // Fail now rather than on the first line
+        resolve_project(client, &mut cache, name).await?;
+    }
+
+    println!(
+        "✏️ One task per line, Ctrl-D to finish. Add ~project #tag !priority @when as needed."
+    );
+    let mut created = 0;
+    loop {
+        print!("› ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            println!();
+            break;
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match capture_line(client, &mut cache, line, project).await {
+            Ok(task) => {
+                created += 1;
+                let due = task
+                    .due_date
+                    .as_ref()
+                    .map(|_| format!(", due {}", format_due(&task)))
+                    .unwrap_or_default();
+                println!("  ✅ {}{}", task.title, due);
+            }
+            Err(e) => println!("  ❌ {}", e),
+        }
+    }
+
+    cache.save()?;
+    println!("👋 Captured {} task(s)", created);
+    Ok(())
+}
+
+pub async fn run_edit(client: &TickTickClient, args: EditArgs) -> Result<()> {
+    let mut cache = Cache::load();
+    let mut task = resolve_task(client, &mut cache, &args.task_id).await?;
+
+    if let Some(title) = args.title {
+        task.title = title;
+    }
+    if let Some(content) = args.content {
+        task.content = Some(content);
+    }
+    if let Some(priority) = args.priority {
+        task.priority = Some(priority.value());
+    }
+    if let Some(due) = &args.due {
+        let (due_date, is_all_day) = checked_due_date(due, args.yes)?;
+        task.start_date = Some(due_date.clone());
+        task.due_date = Some(due_date);
+        task.is_all_day = Some(is_all_day);
+    }
+
+    let task = client.update_task(&task).await?;
+    println!("✅ Updated task:");
+    display::to_stdout(|out| render_task_simple(out, &task, &[]))?;
+
+    cache.upsert_task(&task);
+    cache.save()?;
+    Ok(())
+}
+
+pub async fn run_show(client: &TickTickClient, task_id: &str) -> Result<()> {
+    let mut cache = Cache::load();
+    let cached = resolve_task(client, &mut cache, task_id).await?;
+
+    // Fetch the latest copy so the details aren't stale
+    let task = client.get_task(&cached.project_id, &cached.id).await?;
+    let project_name = if task.project_id.starts_with("inbox") {
+        "Inbox".to_string()
+    } else {
+        cache
+            .find_project(&task.project_id)
+            .map(|project| match display::project_swatch(project) {
+                Some(swatch) => format!("{} {}", swatch, project.name),
+                None => project.name.clone(),
+            })
+            .unwrap_or_else(|| "Unknown Project".to_string())
+    };
+
+    println!();
+    let blockers = cache
+        .blocked_by
+        .get(&task.id)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    display::to_stdout(|out| render_task_details(out, &task, &project_name, blockers))?;
+
+    cache.upsert_task(&task);
+    cache.save()?;
+    Ok(())
+}
+
+/// Print every step that decides whether a task shows up in `tick today`
+pub async fn run_explain(client: &TickTickClient, task_id: &str) -> Result<()> {
+    let mut cache = Cache::load();
+    let cached = resolve_task(client, &mut cache, task_id).await?;
+
+    // Explain the latest copy, since a stale cache is one reason a task goes missing
+    let task = client.get_task(&cached.project_id, &cached.id).await?;
+    let (project_name, scope_name) = if task.project_id.starts_with("inbox") {
+        ("Inbox".to_string(), "inbox".to_string())
+    } else {
+        let name = cache
+            .find_project(&task.project_id)
+            .map(|project| project.name.clone())
+            .unwrap_or_else(|| "Unknown Project".to_string());
+        (name.clone(), name)
+    };
+
+    println!();
+    let lines = explain::explain(
+        &task,
+        &project_name,
+        client.project_in_scope(&scope_name),
+        client.request_limit(),
+    );
+    for line in lines {
+        println!("{}", line);
+    }
+
+    cache.upsert_task(&task);
+    cache.save()?;
+    Ok(())
+}
+
+/// Add a line at the end of a task's content. A line right after a list item continues the
+/// list; after anything else it starts a new paragraph, so it doesn't run into the text above.
+fn append_to_content(content: Option<&str>, line: &str) -> String {
+    let content = content.unwrap_or_default().trim_end();
+    if content.is_empty() {
+        return line.to_string();
+    }
+
+    let last_line = content.lines().last().unwrap_or_default().trim_start();
+    let in_list = ["- ", "* ", "+ "]
+        .iter()
+        .any(|marker| last_line.starts_with(marker));
+    let separator = if in_list { "\n" } else { "\n\n" };
+    format!("{}{}{}", content, separator, line)
+}
+
+/// Append a timestamped bullet to a task's notes, keeping what's already there
+pub async fn run_note(client: &TickTickClient, task_id: &str, text: &str) -> Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(anyhow!("The note is empty"));
+    }
+
+    let mut cache = Cache::load();
+    let cached = resolve_task(client, &mut cache, task_id).await?;
+
+    // Append to the latest content, not a cached copy that may miss recent edits
+    let mut task = client.get_task(&cached.project_id, &cached.id).await?;
+    let bullet = format!("- {} {}", Local::now().format("%Y-%m-%d %H:%M"), text);
+    task.content = Some(append_to_content(task.content.as_deref(), &bullet));
+    let task = client.update_task(&task).await?;
+    println!("📝 Added a note to \"{}\": {}", task.title, text);
+
+    cache.upsert_task(&task);
+    cache.save()?;
+    Ok(())
+}
+
+/// `[hooks]` from the config file; a file that can't be loaded turns them off with a warning
+fn completion_hooks() -> HooksConfig {
+    if !Config::exists() {
+        return HooksConfig::default();
+    }
+    match Config::load() {
+        Ok(config) => config.hooks,
+        Err(e) => {
+            println!("⚠️ Completion hooks are off: {}", e);
+            HooksConfig::default()
+        }
+    }
+}
+
+/// Complete a task, or each task in a range of the last listing, then run the completion
+/// hooks for each one that was completed
+pub async fn run_done(client: &TickTickClient, task_ref: &str) -> Result<()> {
+    let hooks = completion_hooks();
+    let mut completed = Vec::new();
+    let result = complete_tasks(client, task_ref, &mut completed).await;
+
+    // Hooks run once the cache is saved, so a hook that runs tick itself sees these completions
+    for (task, project_name) in &completed {
+        hooks::task_completed(&hooks, task, project_name).await;
+    }
+    result
+}
+
+/// Complete the tasks `task_ref` stands for, adding each to `completed` with its project name
+async fn complete_tasks(
+    client: &TickTickClient,
+    task_ref: &str,
+    completed: &mut Vec<(Task, String)>,
+) -> Result<()> {
+    let mut cache = Cache::load();
+    for task_id in listing::expand(task_ref)? {
+        let task = find_task(client, &mut cache, &task_id).await?;
+
+        client.complete_task(&task).await?;
+        println!("✅ Completed: {}", task.title);
+        if let Err(e) = history::record_completions(std::slice::from_ref(&task), false) {
+            println!("⚠️ Failed to update history: {}", e);
+        }
+        let project_name = if task.project_id.starts_with("inbox") {
+            "Inbox".to_string()
+        } else {
+            cache
+                .find_project(&task.project_id)
+                .map(|project| project.name.clone())
+                .unwrap_or_default()
+        };
+
+        cache.remove_task(&task.id);
+        cache.save()?;
+        completed.push((task, project_name));
+    }
+    Ok(())
+}
+
+/// Delete a task, or each task in a range of the last listing, asking about each one
+pub async fn run_delete(client: &TickTickClient, task_ref: &str, yes: bool) -> Result<()> {
+    let mut cache = Cache::load();
+    for task_id in listing::expand(task_ref)? {
+        let task = find_task(client, &mut cache, &task_id).await?;
+
+        if !yes && !confirm(&format!("🗑️ Delete task \"{}\"?", task.title))? {
+            println!("Skipped: {}", task.title);
+            continue;
+        }
+
+        client.delete_task(&task).await?;
+        println!("🗑️ Deleted: {}", task.title);
+
+        cache.remove_task(&task.id);
+        cache.save()?;
+    }
+    Ok(())
+}
+
+pub fn run_log(limit: usize) -> Result<()> {
+    let entries = audit::read_entries()?;
+    if entries.is_empty() {
+        println!("📜 The audit log is empty");
+        return Ok(());
+    }
+
+    let shown = &entries[entries.len().saturating_sub(limit)..];
+    println!(
+        "📜 Showing {} of {} audit log entries:",
+        shown.len(),
+        entries.len()
+    );
+    println!();
+
+    for entry in shown {
+        let status_icon = if (200..300).contains(&entry.status) {
+            "✅"
+        } else {
+            "❌"
+        };
+        println!(
+            "{} {}  {:<8} {:>3}  {}",
+            status_icon,
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.action.to_string(),
+            entry.status,
+            entry.summary
+        );
+        if let Some(task_id) = &entry.task_id {
+            println!("     🆔 {}", task_id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Search the content and description of cached tasks, printing matches with context
+pub fn run_grep(args: GrepArgs) -> Result<()> {
+    let pattern = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .map_err(|e| anyhow!("Invalid pattern: {}", e))?;
+    let before = args.before_context.or(args.context).unwrap_or(0);
+    let after = args.after_context.or(args.context).unwrap_or(0);
+
+    let cache = Cache::load();
+    if cache.updated_at.is_none() {
+        return Err(anyhow!(
+            "No tasks have been synced yet, run `tick list` first"
+        ));
+    }
+
+    let project_name = |project_id: &str| {
+        if project_id.starts_with("inbox") {
+            "Inbox".to_string()
+        } else {
+            cache
+                .find_project(project_id)
+                .map(|project| project.name.clone())
+                .unwrap_or_else(|| "Unknown Project".to_string())
+        }
+    };
+    let mut tasks: Vec<(String, &Task)> = cache
+        .tasks
+        .values()
+        .flatten()
+        .map(|task| (project_name(&task.project_id), task))
+        .collect();
+    tasks.sort_by(|(a_project, a), (b_project, b)| {
+        (a_project, &a.title).cmp(&(b_project, &b.title))
+    });
+
+    let mut matched = 0;
+    display::to_stdout(|out| {
+        for (project, task) in &tasks {
+            let fields = [("content", &task.content), ("desc", &task.desc)];
+            let hits: Vec<(&str, Vec<Vec<grep::Line>>)> = fields
+                .iter()
+                .filter_map(|(field, text)| {
+                    let blocks = grep::context_blocks(text.as_deref()?, &pattern, before, after);
+                    (!blocks.is_empty()).then_some((*field, blocks))
+                })
+                .collect();
+            if hits.is_empty() {
+                continue;
+            }
+
+            matched += 1;
+            if args.tasks_with_matches {
+                writeln!(out, "{} › {}  🆔 {}", project, task.title, task.id)?;
+                continue;
+            }
+
+            if matched > 1 {
+                writeln!(out)?;
+            }
+            // ANSI codes: \x1b[35m = magenta, \x1b[1m = bold, \x1b[0m = reset
+            writeln!(
+                out,
+                "\x1b[35m{}\x1b[0m › \x1b[1m{}\x1b[0m  🆔 {}",
+                project, task.title, task.id
+            )?;
+            for (field, blocks) in &hits {
+                for (index, block) in blocks.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(out, "  --")?;
+                    }
+                    for line in block {
+                        // Like grep, `:` marks a matching line and `-` a line of context
+                        let separator = if line.is_match { ':' } else { '-' };
+                        writeln!(
+                            out,
+                            "  {}{}{}{} {}",
+                            field,
+                            separator,
+                            line.number,
+                            separator,
+                            grep::highlight(line.text, &pattern)
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    if matched == 0 {
+        eprintln!("🔎 No tasks match");
+    } else {
+        eprintln!("🔎 {} matching task(s)", matched);
+    }
+    Ok(())
+}
+
+pub async fn run_list(
+    client: &TickTickClient,
+    name: Option<&str>,
+    filter: Option<&str>,
+) -> Result<()> {
+    let filter = match (name, filter) {
+        (Some(name), _) => {
+            let config = Config::load()?;
+            let saved = config.filters.get(name).ok_or_else(|| {
+                let available: Vec<&str> = config.filters.keys().map(|k| k.as_str()).collect();
+                anyhow!(
+                    "No saved filter named '{}' (available: {})",
+                    name,
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                )
+            })?;
+            Some(Filter::parse(&saved.query).map_err(|e| anyhow!("Filter '{}': {}", name, e))?)
+        }
+        (None, Some(expression)) => Some(Filter::parse(expression)?),
+        (None, None) => None,
+    };
+
+    let cache = synced_cache(client).await?;
+    println!();
+
+    let tasks = matching_tasks(&cache, filter.as_ref());
+
+    if tasks.is_empty() {
+        println!("🔎 {}", text().no_matching_tasks);
+        return Ok(());
+    }
+
+    println!("📋 {}", (text().matching_tasks)(tasks.len()));
+    println!();
+
+    render_listing("list", &tasks, &cache.projects, &cache.blocked_by)?;
+
+    Ok(())
+}
+
+/// List tasks blocked by open dependencies, or with `ready`, open tasks that aren't blocked
+pub async fn run_dependency_view(
+    client: &TickTickClient,
+    ready: bool,
+    filter: Option<&str>,
+) -> Result<()> {
+    let filter = filter.map(Filter::parse).transpose()?;
+    let cache = synced_cache(client).await?;
+    println!();
+
+    let tasks: Vec<Task> = matching_tasks(&cache, filter.as_ref())
+        .into_iter()
+        .filter(|task| cache.blocked_by.contains_key(&task.id) != ready)
+        .collect();
+
+    if tasks.is_empty() {
+        if ready {
+            println!("🔎 {}", text().no_ready_tasks);
+        } else {
+            println!("🎉 {}", text().no_blocked_tasks);
+        }
+        return Ok(());
+    }
+
+    if ready {
+        println!("🟢 {}", (text().ready_tasks)(tasks.len()));
+    } else {
+        println!("⛔ {}", (text().blocked_tasks)(tasks.len()));
+    }
+    println!();
+
+    let command = if ready { "ready" } else { "blocked" };
+    render_listing(command, &tasks, &cache.projects, &cache.blocked_by)?;
+
+    Ok(())
+}
+
+/// Show a calendar grid for the week `offset` weeks away from the current one
+pub async fn run_week(client: &TickTickClient, offset: i64) -> Result<()> {
+    let cache = synced_cache(client).await?;
+    println!();
+
+    let today = Local::now().date_naive();
+    let week_start =
+        DateWindow::week(today, i18n::first_day()).start + chrono::Duration::weeks(offset);
+    let mut days: Vec<(NaiveDate, Vec<&Task>)> = (0..7)
+        .map(|day| (week_start + Days::new(day), Vec::new()))
+        .collect();
+    let shows_today = days.iter().any(|(date, _)| *date == today);
+
+    let mut overdue = Vec::new();
+    for task in cache.tasks.values().flatten() {
+        let Some(due) = task.due_date.as_deref().and_then(parse_api_datetime) else {
+            continue;
+        };
+        let due_date = due.date_naive();
+
+        if let Some((_, tasks)) = days.iter_mut().find(|(date, _)| *date == due_date) {
+            tasks.push(task);
+        } else if shows_today && due_date < week_start {
+            overdue.push(task);
+        }
+    }
+
+    let due_key = |task: &&Task| task.due_date.as_deref().and_then(parse_api_datetime);
+    overdue.sort_by_key(due_key);
+    for (_, tasks) in &mut days {
+        tasks.sort_by_key(due_key);
+    }
+
+    println!(
+        "🗓️ {}",
+        (text().week_of)(week_start, week_start + Days::new(6))
+    );
+    display::to_stdout(|out| render_week_grid(out, &days, &overdue))?;
+
+    Ok(())
+}
+
+/// Number of open cached tasks due on each day
+fn due_per_day(cache: &Cache) -> HashMap<NaiveDate, usize> {
+    let mut counts = HashMap::new();
+    for task in cache.tasks.values().flatten() {
+        if let Some(due) = task.due_date.as_deref().and_then(parse_api_datetime) {
+            *counts.entry(due.date_naive()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Show a heatmap of tasks due (or completed) per day for the month `offset` months away
+pub async fn run_month(client: &TickTickClient, completed: bool, offset: i32) -> Result<()> {
+    // Syncing also brings the completion history up to date
+    let cache = synced_cache(client).await?;
+    println!();
+
+    let today = Local::now().date_naive();
+    let this_month = today.with_day(1).unwrap_or(today);
+    let month_start = if offset >= 0 {
+        this_month + chrono::Months::new(offset.unsigned_abs())
+    } else {
+        this_month - chrono::Months::new(offset.unsigned_abs())
+    };
+
+    let counts = if completed {
+        history::completions_per_day()?
+    } else {
+        due_per_day(&cache)
+    };
+
+    display::to_stdout(|out| render_month_heatmap(out, month_start, &counts, completed))?;
+    if completed {
+        println!();
+        println!("ℹ️ {}", text().completions_note);
+    }
+
+    Ok(())
+}
+
+/// Move a task's due date according to a date expression, shifting its start date along with it.
+/// Overdue tasks are snoozed relative to today rather than their original due date.
+fn snooze_task(task: &mut Task, shift: DateShift) -> Result<()> {
+    let now = Local::now();
+    let current_due = task.due_date.as_deref().and_then(parse_api_datetime);
+    let at_midnight = |date: chrono::NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+            .unwrap_or(now)
+    };
+
+    let new_due: DateTime<Local> = match (shift, current_due) {
+        (DateShift::By(duration), Some(due)) if due >= now => shift_datetime(due, duration)?,
+        (DateShift::By(duration), Some(due)) => {
+            let today_at_same_time = Local
+                .from_local_datetime(&now.date_naive().and_time(due.time()))
+                .earliest()
+                .unwrap_or(now);
+            shift_datetime(today_at_same_time, duration)?
+        }
+        (DateShift::By(duration), None) if duration.num_seconds() % 86_400 == 0 => {
+            shift_datetime(at_midnight(now.date_naive()), duration)?
+        }
+        (DateShift::By(duration), None) => shift_datetime(now, duration)?,
+        (DateShift::To(date), Some(due)) => Local
+            .from_local_datetime(&date.and_time(due.time()))
+            .earliest()
+            .unwrap_or_else(|| at_midnight(date)),
+        (DateShift::To(date), None) => at_midnight(date),
+    };
+
+    let new_start = match (
+        task.start_date.as_deref().and_then(parse_api_datetime),
+        current_due,
+    ) {
+        (Some(start), Some(due)) => shift_datetime(start, new_due - due)?,
+        _ => new_due,
+    };
+
+    if current_due.is_none() {
+        task.is_all_day = Some(new_due.time() == NaiveTime::MIN);
+    }
+    task.start_date = Some(to_api_datetime(new_start));
+    task.due_date = Some(to_api_datetime(new_due));
+    Ok(())
+}
+
+fn format_due(task: &Task) -> String {
+    task.due_date
+        .as_deref()
+        .and_then(parse_api_datetime)
+        .map(|due| {
+            format!(
+                "{} {}",
+                (text().short_date)(due.date_naive()),
+                due.format(text().time_format)
+            )
+        })
+        .unwrap_or_else(|| text().no_due_date.to_string())
+}
+
+/// Snooze a task, or each task in a range of the last listing
+pub async fn run_snooze(client: &TickTickClient, task_ref: &str, when: &str) -> Result<()> {
+    let shift = parse_date_shift(when)?;
+    let mut cache = Cache::load();
+    for task_id in listing::expand(task_ref)? {
+        let mut task = find_task(client, &mut cache, &task_id).await?;
+
+        snooze_task(&mut task, shift)?;
+        let task = client.update_task(&task).await?;
+        println!("😴 {}", (text().snoozed)(&task.title, &format_due(&task)));
+
+        cache.upsert_task(&task);
+        cache.save()?;
+    }
+    Ok(())
+}
+
+/// Propose the day with the fewest tasks due among the `within` days from tomorrow, the
+/// earliest of them on a tie, and snooze the task there once confirmed
+pub async fn run_snooze_auto(client: &TickTickClient, task_id: &str, within: u64) -> Result<()> {
+    let mut cache = synced_cache(client).await?;
+    let mut task = resolve_task(client, &mut cache, task_id).await?;
+
+    let counts = due_per_day(&cache);
+    let current_due = task
+        .due_date
+        .as_deref()
+        .and_then(parse_api_datetime)
+        .map(|due| due.date_naive());
+    let today = Local::now().date_naive();
+    let days: Vec<(NaiveDate, usize)> = (1..=within)
+        .map(|offset| {
+            let date = today + Days::new(offset);
+            // The task itself doesn't add to the load of the day it's already due
+            let load = counts.get(&date).copied().unwrap_or(0);
+            (
+                date,
+                load.saturating_sub(usize::from(current_due == Some(date))),
+            )
+        })
+        .collect();
+    let Some((best, _)) = days
+        .iter()
+        .copied()
+        .min_by_key(|(date, load)| (*load, *date))
+    else {
+        return Err(anyhow!("No days to choose from"));
+    };
+
+    println!("📊 {}", (text().due_over_next_days)(days.len()));
+    for (date, load) in &days {
+        let mark = if *date == best {
+            format!("  ← {}", text().least_busy)
+        } else {
+            String::new()
+        };
+        println!(
+            "  {}  {:>2} {}{}",
+            i18n::short_day(*date),
+            load,
+            "▇".repeat((*load).min(30)),
+            mark
+        );
+    }
+    println!();
+
+    let question = format!(
+        "😴 {}",
+        (text().snooze_question)(&task.title, &i18n::short_day(best))
+    );
+    if !confirm(&question)? {
+        println!("{}", text().cancelled);
+        return Ok(());
+    }
+
+    snooze_task(&mut task, DateShift::To(best))?;
+    let task = client.update_task(&task).await?;
+    println!("😴 {}", (text().snoozed)(&task.title, &format_due(&task)));
+
+    cache.upsert_task(&task);
+    cache.save()?;
+    Ok(())
+}
+
+/// A bulk action with its arguments already parsed
+#[derive(Clone, Copy)]
+enum BulkChange {
+    Snooze(DateShift),
+    SetPriority(Priority),
+}
+
+pub async fn run_bulk(client: &TickTickClient, args: BulkArgs) -> Result<()> {
+    let filter = Filter::parse(&args.filter)?;
+    let change = match &args.action {
+        BulkAction::Snooze { when } => BulkChange::Snooze(parse_date_shift(when)?),
+        BulkAction::SetPriority { priority } => BulkChange::SetPriority(*priority),
+    };
+
+    // Always work from fresh data so the preview matches what will change
+    let mut cache = synced_cache(client).await?;
+    let mut tasks = matching_tasks(&cache, Some(&filter));
+
+    if tasks.is_empty() {
+        println!("🔎 No tasks match the filter");
+        return Ok(());
+    }
+
+    let originals: Vec<String> = tasks.iter().map(format_due).collect();
+    for task in &mut tasks {
+        match change {
+            BulkChange::Snooze(shift) => snooze_task(task, shift)?,
+            BulkChange::SetPriority(priority) => task.priority = Some(priority.value()),
+        }
+    }
+
+    println!();
+    println!("📋 {} task(s) will be changed:", tasks.len());
+    for (task, original_due) in tasks.iter().zip(&originals) {
+        match change {
+            BulkChange::Snooze(_) => println!(
+                "  • {}  ({} → {})",
+                task.title,
+                original_due,
+                format_due(task)
+            ),
+            BulkChange::SetPriority(priority) => {
+                println!("  • {}  (priority → {:?})", task.title, priority)
+            }
+        }
+    }
+    println!();
+
+    if !args.yes && !confirm("Apply these changes?")? {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for task in &tasks {
+        match client.update_task(task).await {
+            Ok(updated) => cache.upsert_task(&updated),
+            Err(e) => {
+                failed += 1;
+                println!("  ❌ {}: {}", task.title, e);
+            }
+        }
+    }
+    cache.save()?;
+
+    println!(
+        "✅ Updated {} of {} task(s)",
+        tasks.len() - failed,
+        tasks.len()
+    );
+    if failed > 0 {
+        return Err(anyhow!("{} update(s) failed", failed));
+    }
+    Ok(())
+}
+
+/// Move a task within its project by rewriting sortOrder values
+pub async fn run_reorder(client: &TickTickClient, args: ReorderArgs) -> Result<()> {
+    let movement = match (args.up, args.down, args.top, args.bottom) {
+        (Some(places), ..) => Move::Up(places),
+        (_, Some(places), ..) => Move::Down(places),
+        (_, _, true, _) => Move::Top,
+        (_, _, _, true) => Move::Bottom,
+        _ => unreachable!("clap requires one of --up, --down, --top or --bottom"),
+    };
+
+    // Neighbouring sortOrder values must be current, or the task may land in the wrong place
+    let mut cache = synced_cache(client).await?;
+    let task = resolve_task(client, &mut cache, &args.task_id).await?;
+
+    let ordered = reorder::in_app_order(
+        cache
+            .tasks
+            .values()
+            .flatten()
+            .filter(|other| other.project_id == task.project_id),
+    );
+    let from = ordered
+        .iter()
+        .position(|other| other.id == task.id)
+        .ok_or_else(|| anyhow!("Task '{}' is not open", task.title))?;
+    let (to, updates) = reorder::plan(&ordered, from, movement);
+    let total = ordered.len();
+
+    if updates.is_empty() {
+        println!(
+            "↕️ \"{}\" is already at position {} of {}",
+            task.title,
+            to + 1,
+            total
+        );
+        return Ok(());
+    }
+
+    for (id, sort_order) in &updates {
+        let mut changed = cache
+            .find_task(id)?
+            .cloned()
+            .ok_or_else(|| anyhow!("Task {} disappeared from the cache", id))?;
+        changed.sort_order = Some(*sort_order);
+        let updated = client.update_task(&changed).await?;
+        cache.upsert_task(&updated);
+    }
+    cache.save()?;
+
+    println!(
+        "↕️ Moved \"{}\" to position {} of {}",
+        task.title,
+        to + 1,
+        total
+    );
+    if updates.len() > 1 {
+        println!("   Renumbered {} tasks to make room", updates.len());
+    }
+    Ok(())
+}
+
+/// Print the daily digest to stdout, or post it to a webhook; progress messages go to stderr so
+/// the output can be piped
+pub async fn run_digest(
+    client: &TickTickClient,
+    format: DigestFormat,
+    target: Option<NotifyTarget>,
+) -> Result<()> {
+    let cache = synced_cache(client).await?;
+    let completions = history::completions_per_day()?;
+    let digest = Digest::build(&cache, &completions);
+
+    if let Some(target) = target {
+        let config = Config::load()?;
+        notify::send_digest(target, &config.notify, &digest).await?;
+        println!("📨 {}", (text().digest_posted)(&target.to_string()));
+        return Ok(());
+    }
+
+    let output = match format {
+        DigestFormat::Text => digest.to_text(),
+        DigestFormat::Html => digest.to_html(),
+    };
+    io::stdout().write_all(output.as_bytes())?;
+
+    Ok(())
+}
+
+/// Create tasks for new `TODO(tick):` comments and tag each comment with its task id
+pub async fn run_todos_scan(client: &TickTickClient, args: TodosScanArgs) -> Result<()> {
+    let found = todos::scan(&args.path)?;
+    let total: usize = found.iter().map(|(_, todos)| todos.len()).sum();
+    if total == 0 {
+        println!("🔎 No new TODO(tick): comments in {}", args.path.display());
+        return Ok(());
+    }
+
+    // Paths relative to the current directory read better without the leading ./
+    let location = |path: &std::path::Path, line: usize| {
+        format!(
+            "{}:{}",
+            path.strip_prefix(".").unwrap_or(path).display(),
+            line
+        )
+    };
+
+    if args.dry_run {
+        println!("📋 {} comment(s) would become tasks:", total);
+        for (path, todos) in &found {
+            for todo in todos {
+                println!("  • {}  ({})", todo.text, location(path, todo.line));
+            }
+        }
+        return Ok(());
+    }
+
+    let mut cache = Cache::load();
+    let project = match args.project {
+        Some(project) => Some(project),
+        None => Config::load()?.todos.project,
+    };
+    let project_id = match &project {
+        Some(name) => Some(resolve_project(client, &mut cache, name).await?.id),
+        None => None,
+    };
+
+    let mut created = 0;
+    let mut failed = 0;
+    let mut unmarked = 0;
+    for (path, todos) in &found {
+        let mut tracked = Vec::new();
+        for todo in todos {
+            let new_task = NewTask {
+                title: todo.text.clone(),
+                project_id: project_id.clone(),
+                content: Some(location(path, todo.line)),
+                ..Default::default()
+            };
+            match client.create_task(&new_task).await {
+                Ok(task) => {
+                    println!("  ✅ {}  ({})", task.title, location(path, todo.line));
+                    cache.upsert_task(&task);
+                    tracked.push((todo, task.id));
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("  ❌ {}: {}", todo.text, e);
+                }
+            }
+        }
+
+        created += tracked.len();
+        // The tasks exist now, so a file that can't be updated is reported and the rest go on
+        if !tracked.is_empty() {
+            if let Err(e) = todos::mark_tracked(path, &tracked) {
+                unmarked += tracked.len();
+                println!("  ⚠️ {}", e);
+                for (todo, task_id) in &tracked {
+                    println!(
+                        "     Add the task id by hand at {}: TODO(tick:{}):",
+                        location(path, todo.line),
+                        task_id
+                    );
+                }
+            }
+        }
+    }
+    cache.save()?;
+
+    println!(
+        "✅ Created {} of {} task(s) from TODO comments",
+        created, total
+    );
+    if failed > 0 {
+        return Err(anyhow!("{} task(s) could not be created", failed));
+    }
+    if unmarked > 0 {
+        return Err(anyhow!(
+            "{} comment(s) could not be tagged with their task id and would be created again",
+            unmarked
+        ));
+    }
+    Ok(())
+}
+
+/// Midnight local time on `date`, as an all-day due date for the API
+fn all_day_due_date(date: NaiveDate) -> Option<String> {
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(to_api_datetime)
+}
+
+/// Mirror the issues in a repository that are assigned to the token's user. Open issues
+/// become tasks, tasks follow their issue's title, labels and milestone, and closing an
+/// issue completes its task.
+pub async fn run_github_sync(client: &TickTickClient, args: GithubSyncArgs) -> Result<()> {
+    let repo = args.repo.trim_end_matches('/');
+    if repo.split('/').filter(|part| !part.is_empty()).count() != 2 {
+        return Err(anyhow!(
+            "Expected --repo as owner/name, got '{}'",
+            args.repo
+        ));
+    }
+
+    let token = match std::env::var(github::TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.is_empty())
+    {
+        Some(token) => Some(token),
+        None if Config::exists() => {
+            let config = Config::load()?;
+            config.secret(github::TOKEN_SECRET, config.github.token.as_deref())?
+        }
+        None => None,
+    };
+    let token = token.ok_or_else(|| {
+        anyhow!(
+            "No GitHub token: set {} or `tick config set github.token <token>`",
+            github::TOKEN_ENV
+        )
+    })?;
+    let github = GitHub::new(token)?;
+    let login = github.login().await?;
+    eprintln!("🐙 Fetching issues in {} assigned to {}...", repo, login);
+    let issues = github.assigned_issues(repo, &login).await?;
+
+    let mut cache = synced_cache(client).await?;
+    let project_id = match &args.project {
+        Some(name) => Some(resolve_project(client, &mut cache, name).await?.id),
+        None => None,
+    };
+
+    // Open tasks that mirror an issue, keyed by `owner/name#number`
+    let mut tracked: HashMap<String, Task> = cache
+        .tasks
+        .values()
+        .flatten()
+        .filter_map(|task| Some((github::issue_key(task)?.to_string(), task.clone())))
+        .collect();
+    let mut mirrored = github::load_mirrored()?;
+    mirrored.extend(tracked.keys().cloned());
+
+    let mut created = 0;
+    let mut updated = 0;
+    let mut closed = Vec::new();
+    for issue in &issues {
+        let key = format!("{}#{}", repo, issue.number);
+        match tracked.remove(&key) {
+            Some(task) if issue.closed => {
+                client.complete_task(&task).await?;
+                println!("  ✅ Completed: {}  (#{})", task.title, issue.number);
+                cache.remove_task(&task.id);
+                closed.push(task);
+            }
+            Some(mut task) => {
+                let due = task
+                    .due_date
+                    .as_deref()
+                    .and_then(parse_api_datetime)
+                    .map(|due| due.date_naive());
+                let tags = task.tags.clone().unwrap_or_default();
+                let due_changed = issue.due.is_some() && issue.due != due;
+                if task.title == issue.title && tags == issue.tags && !due_changed {
+                    continue;
+                }
+
+                task.title = issue.title.clone();
+                task.tags = Some(issue.tags.clone());
+                if due_changed {
+                    task.due_date = issue.due.and_then(all_day_due_date);
+                    task.is_all_day = Some(true);
+                }
+                let task = client.update_task(&task).await?;
+                println!("  ✏️ Updated: {}  (#{})", task.title, issue.number);
+                cache.upsert_task(&task);
+                updated += 1;
+            }
+            // Closed before it was ever mirrored, or its task was completed or deleted in
+            // TickTick while the issue stayed open
+            None if issue.closed || mirrored.contains(&key) => {}
+            None => {
+                let new_task = NewTask {
+                    title: issue.title.clone(),
+                    project_id: project_id.clone(),
+                    content: Some(format!(
+                        "{}\n\n{}",
+                        issue.url,
+                        github::metadata_line(repo, issue.number)
+                    )),
+                    is_all_day: issue.due.map(|_| true),
+                    due_date: issue.due.and_then(all_day_due_date),
+                    tags: (!issue.tags.is_empty()).then(|| issue.tags.clone()),
+                    ..Default::default()
+                };
+                let task = client.create_task(&new_task).await?;
+                println!("  ➕ Created: {}  (#{})", task.title, issue.number);
+                cache.upsert_task(&task);
+                created += 1;
+
+                mirrored.insert(key);
+                // Saved after every task so a failure part-way through doesn't cause duplicates
+                github::save_mirrored(&mirrored)?;
+            }
+        }
+    }
+
+    github::save_mirrored(&mirrored)?;
+    cache.save()?;
+    if let Err(e) = history::record_completions(&closed, false) {
+        eprintln!("⚠️ Failed to update history: {}", e);
+    }
+
+    println!(
+        "🐙 {} issue(s) assigned to {}: {} created, {} updated, {} completed",
+        issues.len(),
+        login,
+        created,
+        updated,
+        closed.len()
+    );
+    Ok(())
+}
+
+/// Create a task for each unseen message in the configured mailbox. A message is marked seen
+/// only once its task exists, so failed messages are picked up again next time.
+pub async fn run_mail_fetch(client: &TickTickClient, args: MailFetchArgs) -> Result<()> {
+    let config = Config::load()?;
+    let mail_config = config.mail.as_ref().ok_or_else(|| {
+        anyhow!(
+            "No mailbox configured, add a [mail] section with host and username to the config file"
+        )
+    })?;
+
+    let password = mail::password(&config, mail_config)?;
+    eprintln!(
+        "📬 Checking {} on {}...",
+        mail_config.folder, mail_config.host
+    );
+    let mut mailbox = Mailbox::open(mail_config, &password)?;
+    let messages = mailbox.unseen()?;
+    if messages.is_empty() {
+        println!("📭 No unseen messages in {}", mail_config.folder);
+        return mailbox.logout();
+    }
+
+    if args.dry_run {
+        println!("📋 {} message(s) would become tasks:", messages.len());
+        for message in &messages {
+            println!("  • {}  (from {})", message.subject, message.sender);
+        }
+        return mailbox.logout();
+    }
+
+    let mut cache = Cache::load();
+    let project = args.project.or_else(|| mail_config.project.clone());
+    let project_id = match &project {
+        Some(name) => Some(resolve_project(client, &mut cache, name).await?.id),
+        None => None,
+    };
+
+    let mut created = 0;
+    let mut failed = 0;
+    let mut unmarked = 0;
+    for message in &messages {
+        let new_task = NewTask {
+            title: message.subject.clone(),
+            project_id: project_id.clone(),
+            content: Some(message.content()),
+            tags: Some(vec![message.sender_tag()]),
+            ..Default::default()
+        };
+        match client.create_task(&new_task).await {
+            Ok(task) => {
+                println!("  ✅ {}  (from {})", task.title, message.sender);
+                cache.upsert_task(&task);
+                created += 1;
+                // The task exists now, so carry on and save the cache regardless
+                if let Err(e) = mailbox.mark_seen(message.uid) {
+                    unmarked += 1;
+                    println!("  ⚠️ Failed to mark \"{}\" as read: {}", message.subject, e);
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  ❌ {}: {}", message.subject, e);
+            }
+        }
+    }
+    cache.save()?;
+    mailbox.logout()?;
+
+    println!(
+        "✅ Created {} of {} task(s) from mail",
+        created,
+        messages.len()
+    );
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} message(s) could not become tasks and were left unseen",
+            failed
+        ));
+    }
+    if unmarked > 0 {
+        return Err(anyhow!(
+            "{} message(s) became tasks but are still unread; mark them as read before the next \
+             fetch, or their tasks will be created again",
+            unmarked
+        ));
+    }
+    Ok(())
+}
+
+/// Publish task counts to an MQTT broker as retained messages, e.g. from cron
+pub async fn run_mqtt(client: &TickTickClient, args: MqttArgs) -> Result<()> {
+    let cache = synced_cache(client).await?;
+    let completions = history::completions_per_day()?;
+    let digest = Digest::build(&cache, &completions);
+    let state = mqtt::state_payload(&cache, &digest);
+
+    let password = args.username.as_deref().map(mqtt::password).transpose()?;
+    let settings = MqttSettings {
+        broker: args.broker,
+        username: args.username,
+        password,
+        topic_prefix: args.topic_prefix,
+        discovery_prefix: (!args.no_discovery).then_some(args.discovery_prefix),
+    };
+    mqtt::publish(&settings, &state).await?;
+    println!(
+        "📡 Published {} overdue, {} due today to {}/state on {}",
+        digest.overdue.len(),
+        digest.today.len(),
+        settings.topic_prefix,
+        settings.broker
+    );
+
+    Ok(())
+}
+
+/// Task fields for a calendar event; all-day DTEND is exclusive, so the task is due the day before
+fn event_to_task(event: &IcsEvent, project_id: Option<String>) -> NewTask {
+    let content: Vec<&str> = event
+        .location
+        .iter()
+        .chain(event.description.iter())
+        .map(String::as_str)
+        .collect();
+
+    let (start, due, is_all_day) = match (event.start, event.end) {
+        (EventTime::Date(start), end) => {
+            let due = match end {
+                Some(end) if end.date() > start => end.date().pred_opt().unwrap_or(start),
+                _ => start,
+            };
+            let midnight = |date: NaiveDate| {
+                Local
+                    .from_local_datetime(&date.and_time(NaiveTime::MIN))
+                    .earliest()
+            };
+            (midnight(start), midnight(due), true)
+        }
+        (EventTime::DateTime(start), Some(EventTime::DateTime(end))) => {
+            (Some(start), Some(end), false)
+        }
+        (EventTime::DateTime(start), _) => (Some(start), Some(start), false),
+    };
+
+    NewTask {
+        title: event.summary.clone(),
+        project_id,
+        content: (!content.is_empty()).then(|| content.join("\n\n")),
+        is_all_day: Some(is_all_day),
+        start_date: start.map(to_api_datetime),
+        due_date: due.map(to_api_datetime),
+        ..Default::default()
+    }
+}
+
+/// Create tasks from the events in an .ics file, skipping events imported before
+pub async fn run_import_ics(client: &TickTickClient, args: IcsImportArgs) -> Result<()> {
+    let today = Local::now().date_naive();
+    let from = match &args.from {
+        Some(from) => parse_user_datetime(from)?.0.date_naive(),
+        None => today,
+    };
+    let to = match &args.to {
+        Some(to) => parse_user_datetime(to)?.0.date_naive(),
+        None => today + Days::new(30),
+    };
+
+    let content = std::fs::read_to_string(&args.file)
+        .map_err(|e| anyhow!("Failed to read {}: {}", args.file.display(), e))?;
+    let (events, skipped) = ics::parse_events(&content);
+    for reason in &skipped {
+        println!("⚠️ Skipped event {}", reason);
+    }
+    let events: Vec<IcsEvent> = events
+        .into_iter()
+        .filter(|event| (from..=to).contains(&event.start.date()))
+        .collect();
+
+    let mut imported = ics::load_imported()?;
+    let (new_events, already_imported): (Vec<&IcsEvent>, Vec<&IcsEvent>) = events
+        .iter()
+        .partition(|event| !imported.contains_key(&event.import_key()));
+
+    println!(
+        "📅 Found {} event(s) between {} and {}, {} already imported",
+        events.len(),
+        from,
+        to,
+        already_imported.len()
+    );
+    if new_events.is_empty() {
+        return Ok(());
+    }
+    println!();
+
+    if args.dry_run {
+        for event in &new_events {
+            println!("  {} {}", event.start.date(), event.summary);
+        }
+        return Ok(());
+    }
+
+    let mut cache = Cache::load();
+    let project_id = match &args.project {
+        Some(name) => Some(resolve_project(client, &mut cache, name).await?.id),
+        None => None,
+    };
+
+    for event in new_events {
+        let new_task = event_to_task(event, project_id.clone());
+        let added =
+            create_unless_duplicate(client, &mut cache, &new_task, &args.duplicates).await?;
+        if let Added::Created(task) = &added {
+            display::to_stdout(|out| render_task_simple(out, task, &[]))?;
+        }
+
+        // A skipped or updated event stands for the matching task, so it isn't offered again
+        imported.insert(event.import_key(), added.task_id().to_string());
+        // Saved after every task so a failure part-way through doesn't cause duplicates
+        ics::save_imported(&imported)?;
+    }
+
+    cache.save()?;
+    Ok(())
+}
+
+/// Start timing a task, first stopping and logging the timer that's running
+pub async fn run_track_start(client: &TickTickClient, task_id: &str) -> Result<()> {
+    let mut cache = Cache::load();
+    let task = resolve_task(client, &mut cache, task_id).await?;
+
+    if let Some(entry) = track::stop()? {
+        println!(
+            "⏹️ Stopped \"{}\" after {}",
+            entry.title,
+            track::format_duration(entry.duration())
+        );
+    }
+    let timer = track::start(&task)?;
+    println!(
+        "⏱️ Tracking \"{}\" since {}",
+        timer.title,
+        timer.started_at.format("%H:%M")
+    );
+    Ok(())
+}
+
+/// Stop the running timer and log it, also noting the tracked time in the task's content
+/// when a client is given. The task is updated first: if that fails the timer keeps
+/// running, so the stop can be retried.
+pub async fn run_track_stop(client: Option<&TickTickClient>) -> Result<()> {
+    let Some(timer) = track::running()? else {
+        println!("⏱️ No timer is running");
+        return Ok(());
+    };
+    let entry = timer.stopped_now();
+    let tracked = track::format_duration(entry.duration());
+
+    if let Some(client) = client {
+        let line = format!(
+            "⏱️ Tracked {} on {}",
+            tracked,
+            entry.started_at.format("%Y-%m-%d")
+        );
+        let task = append_tracked_time(client, &entry, &line)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to add the tracked time to \"{}\", the timer is still running: {}",
+                    entry.title,
+                    e
+                )
+            })?;
+
+        let mut cache = Cache::load();
+        cache.upsert_task(&task);
+        cache.save()?;
+    }
+
+    track::log(&entry)?;
+    println!("⏹️ Tracked {} on \"{}\"", tracked, entry.title);
+    if client.is_some() {
+        println!("📝 Added the tracked time to the task's notes");
+    }
+    Ok(())
+}
+
+/// Append `line` to the notes of the entry's task, returning the updated task
+async fn append_tracked_time(
+    client: &TickTickClient,
+    entry: &track::Entry,
+    line: &str,
+) -> Result<Task> {
+    let mut task = client.get_task(&entry.project_id, &entry.task_id).await?;
+    task.content = Some(append_to_content(task.content.as_deref(), line));
+    client.update_task(&task).await
+}
+
+pub fn run_track_status() -> Result<()> {
+    match track::running()? {
+        Some(timer) => println!(
+            "⏱️ Tracking \"{}\" for {} (since {})",
+            timer.title,
+            track::format_duration(timer.elapsed()),
+            timer.started_at.format("%b %d %H:%M")
+        ),
+        None => println!("⏱️ No timer is running"),
+    }
+    Ok(())
+}
+
+/// Tracked time per task and the number of completed tasks over the last `days` days
+pub fn run_stats(days: u32) -> Result<()> {
+    let since = Local::now()
+        .date_naive()
+        .checked_sub_days(Days::new(u64::from(days.saturating_sub(1))))
+        .ok_or_else(|| anyhow!("--days {} reaches out of the supported date range", days))?;
+
+    // Totals per task id, keeping the most recent title
+    let mut tracked: Vec<(String, String, chrono::Duration)> = Vec::new();
+    let mut add = |task_id: &str, title: &str, duration: chrono::Duration| match tracked
+        .iter_mut()
+        .find(|(id, _, _)| id == task_id)
+    {
+        Some((_, last_title, total)) => {
+            *last_title = title.to_string();
+            *total += duration;
+        }
+        None => tracked.push((task_id.to_string(), title.to_string(), duration)),
+    };
+    // Only the time at or after midnight on `since` counts, for entries and the running timer
+    let start = Local
+        .from_local_datetime(&since.and_time(NaiveTime::MIN))
+        .earliest()
+        .ok_or_else(|| anyhow!("Failed to find the start of {}", since))?;
+    for entry in track::read_entries()? {
+        if entry.stopped_at > start {
+            let duration = track::time_since(entry.started_at, entry.stopped_at, start);
+            add(&entry.task_id, &entry.title, duration);
+        }
+    }
+    let timer = track::running()?;
+    if let Some(timer) = &timer {
+        let duration = track::time_since(timer.started_at, Local::now(), start);
+        add(&timer.task_id, &timer.title, duration);
+    }
+    tracked.sort_by_key(|(_, _, total)| std::cmp::Reverse(*total));
+
+    let completed = history::read_completions()?
+        .iter()
+        .filter(|completion| completion.completed_at.date_naive() >= since)
+        .count();
+
+    println!("📊 Since {} ({} day(s)):", since.format("%b %d"), days);
+    println!("  ✅ {} task(s) completed", completed);
+    if tracked.is_empty() {
+        println!("  ⏱️ No time tracked");
+        return Ok(());
+    }
+
+    let total = tracked
+        .iter()
+        .fold(chrono::Duration::zero(), |sum, (_, _, duration)| {
+            sum + *duration
+        });
+    println!("  ⏱️ {} tracked:", track::format_duration(total));
+    for (task_id, title, duration) in &tracked {
+        let running = timer
+            .as_ref()
+            .is_some_and(|timer| &timer.task_id == task_id);
+        println!(
+            "    {:>8}  {}{}",
+            track::format_duration(*duration),
+            title,
+            if running { " (running)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+pub fn run_config(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Get { key } => match settings::get(&settings::read_document()?, &key)? {
+            Some(value) => println!("{}", value),
+            None => return Err(anyhow!("{} is not set", key)),
+        },
+        ConfigAction::Set { key, value } => {
+            settings::set(settings::read_document()?, &key, value.as_deref())?;
+            match value {
+                Some(value) if !settings::is_secret(&key) => println!("✅ Set {} = {}", key, value),
+                _ => println!("✅ Set {}", key),
+            }
+        }
+        ConfigAction::Edit => edit_config()?,
+        ConfigAction::Encrypt => {
+            migrate_token_storage(TokenStorage::File, TokenStorage::Encrypted)?
+        }
+        ConfigAction::Decrypt => {
+            migrate_token_storage(TokenStorage::Encrypted, TokenStorage::File)?
+        }
+    }
+    Ok(())
+}
+
+/// Edit a copy of the config file and only replace the original once the copy is valid
+fn edit_config() -> Result<()> {
+    let config_path = Config::config_path()?;
+    let original = std::fs::read_to_string(&config_path).map_err(|e| {
+        anyhow!(
+            "Failed to read config file {}: {}",
+            config_path.display(),
+            e
+        )
+    })?;
+    // Kept next to the original rather than in a shared temp directory, since it holds secrets
+    let draft_path = config_path.with_extension("edit.toml");
+    paths::write_private(&draft_path, &original)?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args.next().unwrap_or("vi");
+    let editor_args: Vec<&str> = editor_args.collect();
+
+    let result = loop {
+        let status = std::process::Command::new(program)
+            .args(&editor_args)
+            .arg(&draft_path)
+            .status()
+            .map_err(|e| anyhow!("Failed to start editor '{}': {}", editor, e))?;
+        if !status.success() {
+            break Err(anyhow!(
+                "Editor exited with {}, configuration left unchanged",
+                status
+            ));
+        }
+
+        let edited = std::fs::read_to_string(&draft_path)?;
+        let validation = settings::validate_text(&edited);
+        match validation {
+            Ok(_) if edited == original => {
+                println!("No changes made");
+                break Ok(());
+            }
+            Ok(_) => {
+                paths::write_private(&config_path, &edited)?;
+                println!("✅ Saved configuration to {}", config_path.display());
+                break Ok(());
+            }
+            Err(e) => {
+                println!("❌ {}", e);
+                if !confirm("Edit again?")? {
+                    break Err(anyhow!("Configuration left unchanged"));
+                }
+            }
+        }
+    };
+
+    let _ = std::fs::remove_file(&draft_path);
+    result
+}
+
+/// Decrypt a secret kept in the config file, encrypting it again if `to` is Encrypted
+fn convert_secret(secret: &mut Option<String>, to: TokenStorage) -> Result<()> {
+    if let Some(stored) = secret.take() {
+        let plain = crypt::decrypt(&stored)?;
+        *secret = Some(match to {
+            TokenStorage::Encrypted => crypt::encrypt(&plain)?,
+            _ => plain,
+        });
+    }
+    Ok(())
+}
+
+/// Move the stored token between plain and passphrase-encrypted storage in the config file
+fn migrate_token_storage(from: TokenStorage, to: TokenStorage) -> Result<()> {
+    let mut config = Config::load()?;
+
+    match config.ticktick.token_storage {
+        storage if storage == from => {}
+        TokenStorage::Keyring => {
+            return Err(anyhow!(
+                "Tokens are stored in the system keyring, not in the configuration file"
+            ))
+        }
+        TokenStorage::Encrypted => return Err(anyhow!("Tokens are already encrypted")),
+        TokenStorage::File => return Err(anyhow!("Tokens are not encrypted")),
+    }
+
+    let token = config.oauth_token()?;
+    if let Some(mail) = config.mail.as_mut() {
+        convert_secret(&mut mail.password, to)?;
+    }
+    convert_secret(&mut config.github.token, to)?;
+    config.ticktick.token_storage = to;
+    match token {
+        Some(token) => config.store_oauth_token(&token)?,
+        None => config.save()?,
+    }
+
+    let config_path = Config::config_path()?;
+    match to {
+        TokenStorage::Encrypted => println!(
+            "🔐 Tokens and secrets in {} are now encrypted; set {} to avoid the passphrase prompt",
+            config_path.display(),
+            crypt::PASSPHRASE_ENV
+        ),
+        _ => println!(
+            "🔓 Tokens and secrets in {} are no longer encrypted",
+            config_path.display()
+        ),
+    }
+    Ok(())
+}
+
+/// Existence and permission summary for one path
+fn path_status(path: &std::path::Path, is_dir: bool, secret: bool) -> String {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return "⚪ not created yet".to_string();
+    };
+    if metadata.is_dir() != is_dir {
+        let expected = if is_dir { "a directory" } else { "a file" };
+        return format!("❌ exists but is not {}", expected);
+    }
+
+    let readable = if is_dir {
+        std::fs::read_dir(path).is_ok()
+    } else {
+        std::fs::File::open(path).is_ok()
+    };
+    // Opening for append checks write access without changing the file
+    let writable = if is_dir {
+        !metadata.permissions().readonly()
+    } else {
+        std::fs::OpenOptions::new().append(true).open(path).is_ok()
+    };
+
+    let status = match (readable, writable) {
+        (true, true) => "✅ read/write".to_string(),
+        (true, false) => "⚠️ read-only".to_string(),
+        (false, _) => "❌ not readable".to_string(),
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if secret && metadata.permissions().mode() & 0o077 != 0 {
+            return format!(
+                "{}, ⚠️ readable by other users (chmod 600 {})",
+                status,
+                path.display()
+            );
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+
+    status
+}
+
+pub fn run_man(dir: &std::path::Path) -> Result<()> {
+    let pages = help::write_man_pages(dir)?;
+    println!("📖 Wrote {} man page(s) to {}", pages.len(), dir.display());
+    Ok(())
+}
+
+/// Print every path tick reads or writes with a status for each
+pub fn run_paths() -> Result<()> {
+    let config_file = paths::config_file()?;
+    let entries = [
+        ("Config file", config_file.clone(), false, true),
+        ("Cache", paths::cache_file()?, false, false),
+        ("State directory", paths::state_dir()?, true, false),
+        ("Audit log", paths::audit_log()?, false, false),
+        ("Completion history", paths::history_file()?, false, false),
+        ("Calendar imports", paths::ics_imports_file()?, false, false),
+        ("GitHub issues", paths::github_sync_file()?, false, false),
+        ("Time log", paths::time_log()?, false, false),
+        ("Running timer", paths::timer_file()?, false, false),
+        ("Last listing", paths::last_listing_file()?, false, false),
+    ];
+
+    println!("📂 Paths used by tick:");
+    for (label, path, is_dir, secret) in &entries {
+        println!("  {:<20} {}", label, path.display());
+        println!("  {:<20} {}", "", path_status(path, *is_dir, *secret));
+    }
+
+    if paths::legacy_config_file().as_ref() == Some(&config_file) {
+        println!();
+        println!(
+            "💡 The config file is in its old location. Move it to {} to use the platform default.",
+            paths::platform_config_file()?.display()
+        );
+    }
+
+    Ok(())
+}
@@ -1,12 +1,274 @@
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::crypt;
+use crate::i18n::Language;
+use crate::paths;
+use crate::settings;
+use crate::types::OAuthToken;
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
     #[serde(rename = "ticktick")]
     pub ticktick: TickTickConfig,
+    /// Named filters runnable with `tick list <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub filters: BTreeMap<String, SavedFilter>,
+    /// Webhook targets for `tick digest --notify`
+    #[serde(default, skip_serializing_if = "NotifyConfig::is_empty")]
+    pub notify: NotifyConfig,
+    #[serde(default, skip_serializing_if = "DisplayConfig::is_default")]
+    pub display: DisplayConfig,
+    #[serde(default, skip_serializing_if = "ThemeConfig::is_empty")]
+    pub theme: ThemeConfig,
+    /// Defaults for `tick todos scan`
+    #[serde(default, skip_serializing_if = "TodosConfig::is_empty")]
+    pub todos: TodosConfig,
+    /// Credentials for `tick github sync`
+    #[serde(default, skip_serializing_if = "GithubConfig::is_empty")]
+    pub github: GithubConfig,
+    #[serde(default, skip_serializing_if = "SyncConfig::is_empty")]
+    pub sync: SyncConfig,
+    #[serde(default, skip_serializing_if = "HttpConfig::is_empty")]
+    pub http: HttpConfig,
+    /// What happens after `tick done`
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    /// Mailbox read by `tick mail fetch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mail: Option<MailConfig>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct GithubConfig {
+    /// Personal access token; GITHUB_TOKEN takes precedence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl GithubConfig {
+    fn is_empty(&self) -> bool {
+        self.token.is_none()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct HooksConfig {
+    /// Shell command run after each task completed with `tick done`, with the task in TICK_*
+    /// environment variables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_complete_command: Option<String>,
+    /// Ring the terminal bell when a task is completed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bell: bool,
+}
+
+impl HooksConfig {
+    fn is_empty(&self) -> bool {
+        self.on_complete_command.is_none() && !self.bell
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TodosConfig {
+    /// Project that tasks for TODO comments are created in, the inbox when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+impl TodosConfig {
+    fn is_empty(&self) -> bool {
+        self.project.is_none()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SyncConfig {
+    /// Comma-separated names of the only projects to fetch, e.g. "Inbox, Work"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_scope: Option<String>,
+}
+
+impl SyncConfig {
+    fn is_empty(&self) -> bool {
+        self.project_scope.is_none()
+    }
+
+    /// Project names in the scope, or None when every project is fetched
+    pub fn project_scope(&self) -> Option<Vec<String>> {
+        self.project_scope
+            .as_ref()
+            .map(|scope| scope.split(',').map(|name| name.trim().to_string()).collect())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct HttpConfig {
+    /// Seconds an idle connection to the API is kept for reuse, 90 when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_idle_timeout: Option<u64>,
+}
+
+impl HttpConfig {
+    fn is_empty(&self) -> bool {
+        self.pool_idle_timeout.is_none()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MailConfig {
+    /// IMAP server, connected to over TLS
+    pub host: String,
+    #[serde(default = "default_imap_port")]
+    pub port: u16,
+    pub username: String,
+    /// Asked for on each run when neither this nor TICK_MAIL_PASSWORD is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default = "default_mail_folder")]
+    pub folder: String,
+    /// Project that tasks from mail are created in, the inbox when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+
+fn default_mail_folder() -> String {
+    "INBOX".to_string()
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DisplayConfig {
+    #[serde(default)]
+    pub time_style: TimeStyle,
+    /// Lines of task content shown in lists before the rest is cut off, 5 when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_lines: Option<usize>,
+    #[serde(default)]
+    pub project_order: ProjectOrder,
+    #[serde(default)]
+    pub task_order: TaskOrder,
+    /// Output language; the locale from LANG is used when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// First day of calendar weeks and of `due:week`; the locale from LANG is used when unset
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "weekday_name")]
+    pub first_day_of_week: Option<Weekday>,
+}
+
+impl DisplayConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Weekday names as written in the configuration, Monday first
+pub const WEEKDAY_NAMES: [&str; 7] = [
+    "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+];
+
+/// Weekdays as their full lowercase name, the form `tick config set` accepts, rather than
+/// chrono's "Mon"
+mod weekday_name {
+    use chrono::Weekday;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::WEEKDAY_NAMES;
+
+    pub fn serialize<S: Serializer>(day: &Option<Weekday>, serializer: S) -> Result<S::Ok, S::Error> {
+        match day {
+            Some(day) => serializer.serialize_str(WEEKDAY_NAMES[day.num_days_from_monday() as usize]),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Weekday>, D::Error> {
+        Option::<Weekday>::deserialize(deserializer)
+    }
+}
+
+/// Colors of due times by urgency: overdue, due soon, due today, later
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ThemeConfig {
+    /// Minutes before a timed task is due that it counts as due soon, 60 when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soon_minutes: Option<u64>,
+}
+
+impl ThemeConfig {
+    fn is_empty(&self) -> bool {
+        self.soon_minutes.is_none()
+    }
+}
+
+/// How due and start times are printed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeStyle {
+    /// "Today 05:00 PM", "Tomorrow 09:00 AM", otherwise the date
+    #[default]
+    Relative,
+    /// Always the date, e.g. "May 01 05:00 PM"
+    Absolute,
+    /// "2024-05-01 17:00"
+    Iso,
+}
+
+/// Order of project sections in grouped output
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectOrder {
+    /// Your TickTick order: Inbox, then projects in folders, then the rest
+    #[default]
+    App,
+    /// Inbox, then alphabetically by name
+    Name,
+}
+
+/// Order of tasks within a project
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskOrder {
+    /// Your TickTick order within the project
+    #[default]
+    App,
+    /// Highest priority first
+    Priority,
+    /// Earliest due date first, undated tasks last
+    Due,
+    /// Alphabetically by title
+    Title,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SavedFilter {
+    pub query: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct NotifyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack: Option<WebhookConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord: Option<WebhookConfig>,
+}
+
+impl NotifyConfig {
+    fn is_empty(&self) -> bool {
+        self.slack.is_none() && self.discord.is_none()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    pub webhook_url: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -15,19 +277,132 @@ pub struct TickTickConfig {
     pub client_secret: String,
     #[serde(default = "default_redirect_uri")]
     pub redirect_uri: String,
+    #[serde(default)]
+    pub token_storage: TokenStorage,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub access_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_expires_at: Option<DateTime<Utc>>,
+}
+
+/// Where the OAuth access token is persisted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    /// In the configuration file itself
+    #[default]
+    File,
+    /// In the operating system keyring
+    Keyring,
+    /// In the configuration file, encrypted with a passphrase
+    Encrypted,
 }
 
 pub fn default_redirect_uri() -> String {
     "http://localhost:8080/callback".to_string()
 }
 
+pub fn redirect_uri_for_port(port: u16) -> String {
+    format!("http://localhost:{}/callback", port)
+}
+
+const KEYRING_SERVICE: &str = "tick";
+
+impl TickTickConfig {
+    /// Port the local OAuth callback server listens on, taken from the redirect URI
+    pub fn redirect_port(&self) -> u16 {
+        url::Url::parse(&self.redirect_uri)
+            .ok()
+            .and_then(|url| url.port_or_known_default())
+            .unwrap_or(8080)
+    }
+
+    fn keyring_entry(&self, name: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, &format!("{}:{}", self.client_id, name))
+            .map_err(|e| anyhow!("Failed to access system keyring: {}", e))
+    }
+
+    pub fn keyring_get(&self, name: &str) -> Option<String> {
+        self.keyring_entry(name).ok()?.get_password().ok()
+    }
+
+    pub fn keyring_set(&self, name: &str, value: &str) -> Result<()> {
+        self.keyring_entry(name)?
+            .set_password(value)
+            .map_err(|e| anyhow!("Failed to store {} in keyring: {}", name, e))
+    }
+}
+
 impl Config {
-    fn config_path() -> Result<PathBuf> {
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| anyhow!("Could not find home directory"))?;
-        Ok(home_dir.join(".ticktick.toml"))
+    pub fn config_path() -> Result<PathBuf> {
+        paths::config_file()
+    }
+
+    pub fn exists() -> bool {
+        Self::config_path().map(|path| path.exists()).unwrap_or(false)
+    }
+
+    /// OAuth token from the configured storage backend; encrypted tokens may prompt for the
+    /// passphrase
+    pub fn oauth_token(&self) -> Result<Option<OAuthToken>> {
+        let (access_token, refresh_token) = match self.ticktick.token_storage {
+            TokenStorage::File => (
+                self.ticktick.access_token.clone(),
+                self.ticktick.refresh_token.clone(),
+            ),
+            TokenStorage::Encrypted => (
+                self.ticktick.access_token.as_deref().map(crypt::decrypt).transpose()?,
+                self.ticktick.refresh_token.as_deref().map(crypt::decrypt).transpose()?,
+            ),
+            TokenStorage::Keyring => (
+                self.ticktick.keyring_get("access_token"),
+                self.ticktick.keyring_get("refresh_token"),
+            ),
+        };
+
+        Ok(access_token.map(|access_token| OAuthToken {
+            access_token,
+            refresh_token,
+            expires_at: self.ticktick.token_expires_at,
+        }))
+    }
+
+    /// A secret such as `mail.password` from the token storage backend: the keyring entry
+    /// `name`, or else the value in the file, decrypted if it was encrypted
+    pub fn secret(&self, name: &str, stored: Option<&str>) -> Result<Option<String>> {
+        if self.ticktick.token_storage == TokenStorage::Keyring {
+            if let Some(secret) = self.ticktick.keyring_get(name) {
+                return Ok(Some(secret));
+            }
+        }
+        stored.map(crypt::decrypt).transpose()
+    }
+
+    /// Persist a new OAuth token to the configured storage backend
+    pub fn store_oauth_token(&mut self, token: &OAuthToken) -> Result<()> {
+        match self.ticktick.token_storage {
+            TokenStorage::File => {
+                self.ticktick.access_token = Some(token.access_token.clone());
+                self.ticktick.refresh_token = token.refresh_token.clone();
+            }
+            TokenStorage::Encrypted => {
+                self.ticktick.access_token = Some(crypt::encrypt(&token.access_token)?);
+                self.ticktick.refresh_token =
+                    token.refresh_token.as_deref().map(crypt::encrypt).transpose()?;
+            }
+            TokenStorage::Keyring => {
+                self.ticktick.keyring_set("access_token", &token.access_token)?;
+                if let Some(refresh_token) = &token.refresh_token {
+                    self.ticktick.keyring_set("refresh_token", refresh_token)?;
+                }
+                self.ticktick.access_token = None;
+                self.ticktick.refresh_token = None;
+            }
+        }
+        self.ticktick.token_expires_at = token.expires_at;
+        self.save()
     }
 
     pub fn load() -> Result<Self> {
@@ -35,7 +410,7 @@ impl Config {
         
         if !config_path.exists() {
             return Err(anyhow!(
-                "Configuration file not found at: {}\n\nPlease create this file with your TickTick API credentials:\n\n[ticktick]\nclient_id = \"your_client_id_here\"\nclient_secret = \"your_client_secret_here\"\n# redirect_uri = \"http://localhost:8080/callback\"  # Optional, defaults to this value", 
+                "Configuration file not found at: {}\n\nRun `tick init` to set up your TickTick API credentials.",
                 config_path.display()
             ));
         }
@@ -43,8 +418,8 @@ impl Config {
         let config_content = fs::read_to_string(&config_path)
             .map_err(|e| anyhow!("Failed to read config file {}: {}", config_path.display(), e))?;
         
-        let config: Config = toml::from_str(&config_content)
-            .map_err(|e| anyhow!("Failed to parse config file {}: {}", config_path.display(), e))?;
+        let config = settings::validate_text(&config_content)
+            .map_err(|e| anyhow!("Error in config file {}: {}", config_path.display(), e))?;
         
         Ok(config)
     }
@@ -54,41 +429,6 @@ impl Config {
         let config_content = toml::to_string_pretty(self)
             .map_err(|e| anyhow!("Failed to serialize config: {}", e))?;
         
-        fs::write(&config_path, config_content)
-            .map_err(|e| anyhow!("Failed to write config file {}: {}", config_path.display(), e))?;
-        
-        Ok(())
-    }
-
-    pub fn create_example() -> Result<()> {
-        let config_path = Self::config_path()?;
-
-        if config_path.exists() {
-            return Err(anyhow!("Configuration file already exists at: {}", config_path.display()));
-        }
-
-        let example_config = r#"# TickTick API Configuration
-# Get your client_id and client_secret from the TickTick Developer Center
-# https://developer.ticktick.com/
-
-[ticktick]
-client_id = "your_client_id_here"
-client_secret = "your_client_secret_here"
-
-# Optional: Custom redirect URI (defaults to http://localhost:8080/callback)
-# Make sure this matches what you configured in the TickTick Developer Center
-# redirect_uri = "http://localhost:8080/callback"
-
-# Note: access_token will be automatically added and managed by the application
-# after the first successful OAuth authentication
-"#;
-
-        fs::write(&config_path, example_config)
-            .map_err(|e| anyhow!("Failed to create config file {}: {}", config_path.display(), e))?;
-        
-        println!("✅ Created example configuration file at: {}", config_path.display());
-        println!("📝 Please edit this file with your actual TickTick API credentials.");
-        
-        Ok(())
+        paths::write_private(&config_path, &config_content)
     }
 }
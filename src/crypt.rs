@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::env;
+use std::sync::OnceLock;
+
+/// Environment variable that supplies the passphrase instead of prompting, e.g. for cron jobs
+pub const PASSPHRASE_ENV: &str = "TICK_CONFIG_KEY";
+
+/// Marks an encrypted value: `enc:v1:<salt>:<nonce>:<ciphertext>`, each part base64
+const PREFIX: &str = "enc:v1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Asked for at most once per run
+static PASSPHRASE: OnceLock<String> = OnceLock::new();
+
+fn prompt_passphrase(confirm: bool) -> Result<String> {
+    let read = |prompt: &str| {
+        rpassword::prompt_password(prompt).map_err(|e| {
+            anyhow!(
+                "Failed to read passphrase ({}), set {} to provide it non-interactively",
+                e,
+                PASSPHRASE_ENV
+            )
+        })
+    };
+
+    let passphrase = read("🔐 Config passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(anyhow!("The passphrase must not be empty"));
+    }
+    if confirm && read("🔐 Repeat passphrase: ")? != passphrase {
+        return Err(anyhow!("Passphrases don't match"));
+    }
+    Ok(passphrase)
+}
+
+/// Passphrase from the environment, or prompted for (twice when `confirm` is set)
+fn passphrase(confirm: bool) -> Result<&'static str> {
+    if let Some(passphrase) = PASSPHRASE.get() {
+        return Ok(passphrase);
+    }
+
+    let passphrase = match env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => passphrase,
+        _ => prompt_passphrase(confirm)?,
+    };
+    Ok(PASSPHRASE.get_or_init(|| passphrase))
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive encryption key: {}", e))?;
+    XChaCha20Poly1305::new_from_slice(&key).map_err(|_| anyhow!("Invalid encryption key length"))
+}
+
+/// Encrypt a value with a key derived from the passphrase and a fresh random salt
+pub fn encrypt(plaintext: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher(passphrase(true)?, &salt)?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow!("Failed to encrypt token"))?;
+
+    Ok(format!(
+        "{}{}:{}:{}",
+        PREFIX,
+        BASE64.encode(salt),
+        BASE64.encode(nonce.as_slice()),
+        BASE64.encode(ciphertext)
+    ))
+}
+
+/// Whether a value was produced by `encrypt`
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// Decrypt a value produced by `encrypt`; values without the prefix are returned unchanged
+pub fn decrypt(value: &str) -> Result<String> {
+    let Some(encoded) = value.strip_prefix(PREFIX) else {
+        return Ok(value.to_string());
+    };
+
+    let malformed = || anyhow!("Malformed encrypted value in configuration file");
+    let parts: Vec<Vec<u8>> = encoded
+        .split(':')
+        .map(|part| BASE64.decode(part).map_err(|_| malformed()))
+        .collect::<Result<_>>()?;
+    let [salt, nonce, ciphertext] = parts.as_slice() else {
+        return Err(malformed());
+    };
+    if salt.len() != SALT_LEN || nonce.len() != NONCE_LEN {
+        return Err(malformed());
+    }
+
+    let plaintext = cipher(passphrase(false)?, salt)?
+        .decrypt(XNonce::from_slice(nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow!("Failed to decrypt token, is the passphrase correct?"))?;
+    String::from_utf8(plaintext).map_err(|_| malformed())
+}
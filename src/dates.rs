@@ -0,0 +1,332 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Days, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use dtparse::parse;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::cmp::Ordering;
+
+/// A relative or absolute date expression, e.g. "1d", "2w", "3h", "tomorrow" or "2024-05-01"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateShift {
+    By(Duration),
+    To(NaiveDate),
+}
+
+pub fn parse_date_shift(input: &str) -> Result<DateShift> {
+    let input = input.trim().to_lowercase();
+    let today = Local::now().date_naive();
+
+    match input.as_str() {
+        "today" => return Ok(DateShift::To(today)),
+        "tomorrow" => return Ok(DateShift::To(today + chrono::Days::new(1))),
+        _ => {}
+    }
+
+    if let Some(unit) = input.chars().last().filter(|c| "hdw".contains(*c)) {
+        if let Ok(amount) = input[..input.len() - 1].parse::<i64>() {
+            let duration = match unit {
+                'h' => Duration::try_hours(amount),
+                'd' => Duration::try_days(amount),
+                _ => Duration::try_weeks(amount),
+            };
+            return duration
+                .map(DateShift::By)
+                .ok_or_else(|| anyhow!("'{}' is out of range", input));
+        }
+    }
+
+    Ok(DateShift::To(parse_user_datetime(&input)?.0.date_naive()))
+}
+
+/// Parse a date string returned by the API, honoring its UTC offset when present
+pub fn parse_api_datetime(date_str: &str) -> Option<DateTime<Local>> {
+    let (datetime, offset) = parse(date_str).ok()?;
+    match offset {
+        Some(offset) => offset
+            .from_local_datetime(&datetime)
+            .single()
+            .map(|datetime| datetime.with_timezone(&Local)),
+        None => Local.from_local_datetime(&datetime).earliest(),
+    }
+}
+
+/// Inclusive range of local days that task dates are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateWindow {
+    pub fn day(date: NaiveDate) -> Self {
+        DateWindow {
+            start: date,
+            end: date,
+        }
+    }
+
+    /// The seven days from the last `first_day` on or before `date`
+    pub fn week(date: NaiveDate, first_day: Weekday) -> Self {
+        let into_week =
+            (date.weekday().num_days_from_monday() + 7 - first_day.num_days_from_monday()) % 7;
+        let start = date - Days::new(u64::from(into_week));
+        DateWindow {
+            start,
+            end: start + Days::new(6),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Less before the window, Equal inside it, Greater after it
+    pub fn compare(&self, date: NaiveDate) -> Ordering {
+        if date < self.start {
+            Ordering::Less
+        } else if date > self.end {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+/// `datetime` moved by `duration`, or an error when that leaves the supported date range
+pub fn shift_datetime(datetime: DateTime<Local>, duration: Duration) -> Result<DateTime<Local>> {
+    datetime
+        .checked_add_signed(duration)
+        .ok_or_else(|| anyhow!("Moving {} by {} is out of range", datetime, duration))
+}
+
+/// Format a datetime the way the TickTick API expects, e.g. 2019-11-13T03:00:00+0000
+pub fn to_api_datetime(datetime: DateTime<Local>) -> String {
+    datetime.format("%Y-%m-%dT%H:%M:%S%z").to_string()
+}
+
+/// Parse a user-supplied date like "2024-05-01" or "May 1 5pm" as local time,
+/// returning whether it has no time of day and should be treated as all-day
+pub fn parse_user_datetime(input: &str) -> Result<(DateTime<Local>, bool)> {
+    let (datetime, _) =
+        parse(input).map_err(|e| anyhow!("Could not understand date '{}': {}", input, e))?;
+    let local_datetime = Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .ok_or_else(|| anyhow!("'{}' does not exist in the local timezone", input))?;
+
+    Ok((local_datetime, datetime.time() == NaiveTime::MIN))
+}
+
+/// A time of day in user input, e.g. "5pm", "17:30" or "noon"
+static TIME_OF_DAY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\d:\d\d|\d\s*[ap]\.?m\b|\bnoon\b").unwrap());
+
+/// Midnight asked for on purpose, e.g. "00:00", "12am" or "midnight"
+static EXPLICIT_MIDNIGHT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b0?0:00\b|\b12\s*a\.?m\b|\bmidnight\b").unwrap());
+
+/// Reasons a due date parsed from `input` may not be the one meant: it's in the past, more
+/// than a year away, or at midnight (an all-day task) although the input names a time of day
+pub fn due_date_warnings(
+    input: &str,
+    due: DateTime<Local>,
+    all_day: bool,
+    now: DateTime<Local>,
+) -> Vec<&'static str> {
+    let mut warnings = Vec::new();
+
+    let past = if all_day {
+        due.date_naive() < now.date_naive()
+    } else {
+        due < now
+    };
+    if past {
+        warnings.push("it's in the past");
+    }
+    if due > now + Duration::days(365) {
+        warnings.push("it's more than a year away");
+    }
+    if all_day && TIME_OF_DAY.is_match(input) && !EXPLICIT_MIDNIGHT.is_match(input) {
+        warnings.push("it's at midnight, which makes an all-day task, though a time was given");
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use proptest::prelude::*;
+
+    /// Any day from 1970 through 2099
+    fn date() -> impl Strategy<Value = NaiveDate> {
+        (0i64..47_482)
+            .prop_map(|days| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + Duration::days(days))
+    }
+
+    /// UTC offsets in use by real time zones, -12:00 to +14:00 in quarter hours
+    fn offset() -> impl Strategy<Value = FixedOffset> {
+        (-48i32..=56).prop_map(|quarters| FixedOffset::east_opt(quarters * 15 * 60).unwrap())
+    }
+
+    /// Seconds since the epoch through 2099, covering every DST change of the local zone
+    fn timestamp() -> impl Strategy<Value = i64> {
+        0i64..4_102_444_800
+    }
+
+    // Run with e.g. `TZ=America/New_York cargo test` to exercise a zone with DST
+    proptest! {
+        #[test]
+        fn window_contains_exactly_its_days(start in date(), len in 0i64..60, date in date()) {
+            let window = DateWindow { start, end: start + Duration::days(len) };
+            prop_assert_eq!(window.contains(date), start <= date && date <= window.end);
+            let expected = if date < start {
+                Ordering::Less
+            } else if window.contains(date) {
+                Ordering::Equal
+            } else {
+                Ordering::Greater
+            };
+            prop_assert_eq!(window.compare(date), expected);
+        }
+
+        #[test]
+        fn window_uses_local_day_of_api_date(secs in timestamp(), offset in offset()) {
+            let instant = offset.timestamp_opt(secs, 0).unwrap();
+            let date_str = instant.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string();
+            let local_day = instant.with_timezone(&Local).date_naive();
+
+            let day = parse_api_datetime(&date_str).unwrap().date_naive();
+
+            let today = DateWindow::day(local_day);
+            prop_assert_eq!(today.compare(day), Ordering::Equal);
+
+            let tomorrow = DateWindow::day(local_day + Days::new(1));
+            prop_assert_eq!(tomorrow.compare(day), Ordering::Less);
+        }
+
+        #[test]
+        fn weeks_start_on_the_first_day(date in date(), first in 0u8..7) {
+            let first_day = Weekday::try_from(first).unwrap();
+            let week = DateWindow::week(date, first_day);
+            prop_assert!(week.contains(date));
+            prop_assert_eq!(week.start.weekday(), first_day);
+            prop_assert_eq!(week.end, week.start + Days::new(6));
+        }
+
+        #[test]
+        fn api_datetimes_round_trip(secs in timestamp()) {
+            let datetime = Local.timestamp_opt(secs, 0).unwrap();
+            prop_assert_eq!(parse_api_datetime(&to_api_datetime(datetime)), Some(datetime));
+        }
+
+        #[test]
+        fn unit_shifts_parse_to_durations(
+            amount in prop_oneof![-10_000i64..10_000, any::<i64>()],
+            unit in prop_oneof![Just('h'), Just('d'), Just('w')],
+            upper in any::<bool>(),
+            pad in 0usize..3,
+        ) {
+            let mut input = format!("{}{}{}{}", " ".repeat(pad), amount, unit, " ".repeat(pad));
+            if upper {
+                input = input.to_uppercase();
+            }
+            let expected = match unit {
+                'h' => Duration::try_hours(amount),
+                'd' => Duration::try_days(amount),
+                _ => Duration::try_weeks(amount),
+            };
+            match expected {
+                Some(expected) => {
+                    prop_assert_eq!(parse_date_shift(&input).unwrap(), DateShift::By(expected))
+                }
+                None => prop_assert!(parse_date_shift(&input).is_err()),
+            }
+        }
+
+        #[test]
+        fn iso_dates_parse_to_that_day(date in date()) {
+            // Midnight doesn't exist on days some zones switch to DST at midnight
+            let midnight = Local.from_local_datetime(&date.and_time(NaiveTime::MIN));
+            prop_assume!(midnight.earliest().is_some());
+            let input = date.format("%Y-%m-%d").to_string();
+            prop_assert_eq!(parse_date_shift(&input).unwrap(), DateShift::To(date));
+        }
+
+        #[test]
+        fn user_datetimes_keep_wall_clock_time(
+            date in date(),
+            hour in 0u32..24,
+            minute in 0u32..60,
+        ) {
+            let naive = date.and_hms_opt(hour, minute, 0).unwrap();
+            let input = naive.format("%Y-%m-%d %H:%M").to_string();
+
+            match Local.from_local_datetime(&naive).earliest() {
+                // Times skipped by a DST change are rejected rather than silently moved
+                None => prop_assert!(parse_user_datetime(&input).is_err()),
+                Some(expected) => {
+                    let (datetime, all_day) = parse_user_datetime(&input).unwrap();
+                    prop_assert_eq!(datetime, expected);
+                    prop_assert_eq!(all_day, hour == 0 && minute == 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn suspicious_due_dates_are_flagged() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let at = |day, hour| Local.with_ymd_and_hms(2024, 5, day, hour, 0, 0).unwrap();
+
+        assert!(due_date_warnings("May 2 5pm", at(2, 17), false, now).is_empty());
+        assert!(due_date_warnings("2024-05-01", at(1, 0), true, now).is_empty());
+        assert_eq!(
+            due_date_warnings("May 1 9am", at(1, 9), false, now),
+            ["it's in the past"]
+        );
+        let yesterday = Local.with_ymd_and_hms(2024, 4, 30, 0, 0, 0).unwrap();
+        assert_eq!(
+            due_date_warnings("2024-04-30", yesterday, true, now),
+            ["it's in the past"]
+        );
+
+        let next_year = Local.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap();
+        assert_eq!(
+            due_date_warnings("2025-06-01 09:00", next_year, false, now),
+            ["it's more than a year away"]
+        );
+
+        // A time of day that still came out as midnight, unless midnight was asked for
+        assert_eq!(
+            due_date_warnings("May 3 at 5pm", at(3, 0), true, now).len(),
+            1
+        );
+        assert!(due_date_warnings("May 3 12am", at(3, 0), true, now).is_empty());
+        assert!(due_date_warnings("2024-05-03 00:00", at(3, 0), true, now).is_empty());
+    }
+
+    #[test]
+    fn huge_shifts_are_out_of_range() {
+        assert!(parse_date_shift("+9999999999999w").is_err());
+        assert!(parse_date_shift("9223372036854775807h").is_err());
+
+        // Fits in a Duration but not in the calendar
+        let DateShift::By(far) = parse_date_shift("1000000000w").unwrap() else {
+            panic!("expected a relative shift");
+        };
+        assert!(shift_datetime(Local::now(), far).is_err());
+        assert!(shift_datetime(Local::now(), -far).is_err());
+        assert!(shift_datetime(Local::now(), Duration::days(1)).is_ok());
+    }
+
+    #[test]
+    fn relative_words_parse_from_today() {
+        let today = Local::now().date_naive();
+        assert_eq!(parse_date_shift("Today").unwrap(), DateShift::To(today));
+        assert_eq!(
+            parse_date_shift(" tomorrow ").unwrap(),
+            DateShift::To(today + Days::new(1))
+        );
+    }
+}
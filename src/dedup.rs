@@ -0,0 +1,68 @@
+use chrono::NaiveDate;
+
+use crate::cache::Cache;
+use crate::dates::parse_api_datetime;
+use crate::types::{NewTask, Task};
+
+/// What to do when a new task looks like one that already exists
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnDuplicate {
+    /// Create the task without checking for duplicates
+    #[default]
+    Create,
+    /// Keep the existing task and don't create a new one
+    Skip,
+    /// Copy the new task's fields onto the existing task
+    Update,
+    /// Ask what to do for each duplicate
+    Ask,
+}
+
+/// Lowercase words without punctuation, so "Call Bob!" matches "call  bob"
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn due_day(due_date: Option<&str>) -> Option<NaiveDate> {
+    due_date
+        .and_then(parse_api_datetime)
+        .map(|due| due.date_naive())
+}
+
+/// A cached open task with the same title and due day as `new_task`, and the same notes
+/// when `match_content` is set
+pub fn find<'a>(cache: &'a Cache, new_task: &NewTask, match_content: bool) -> Option<&'a Task> {
+    let title = normalize(&new_task.title);
+    let due = due_day(new_task.due_date.as_deref());
+    let content = normalize(new_task.content.as_deref().unwrap_or_default());
+
+    cache.tasks.values().flatten().find(|task| {
+        task.status == 0
+            && normalize(&task.title) == title
+            && due_day(task.due_date.as_deref()) == due
+            && (!match_content || normalize(task.content.as_deref().unwrap_or_default()) == content)
+    })
+}
+
+/// Copy every field `new_task` sets onto `task`. The project and checklist are left alone.
+pub fn merge(task: &mut Task, new_task: &NewTask) {
+    task.title = new_task.title.clone();
+    if new_task.content.is_some() {
+        task.content = new_task.content.clone();
+    }
+    if new_task.priority.is_some() {
+        task.priority = new_task.priority;
+    }
+    if new_task.tags.is_some() {
+        task.tags = new_task.tags.clone();
+    }
+    if new_task.due_date.is_some() {
+        task.start_date = new_task.start_date.clone();
+        task.due_date = new_task.due_date.clone();
+        task.is_all_day = new_task.is_all_day;
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use crate::types::Task;
+
+/// References from `depends: <task-id|title>` lines in a task's content
+pub fn dependency_refs(task: &Task) -> Vec<&str> {
+    task.content
+        .iter()
+        .flat_map(|content| content.lines())
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case("depends").then(|| value.trim())
+        })
+        .filter(|reference| !reference.is_empty())
+        .collect()
+}
+
+/// Find the open task a dependency refers to, by id, id prefix or exact title
+fn resolve<'a>(reference: &str, tasks: &[&'a Task]) -> Option<&'a Task> {
+    tasks
+        .iter()
+        .find(|task| task.id == reference)
+        .or_else(|| {
+            tasks
+                .iter()
+                .find(|task| task.title.eq_ignore_ascii_case(reference))
+        })
+        .or_else(|| {
+            let mut prefixed = tasks.iter().filter(|task| task.id.starts_with(reference));
+            match (prefixed.next(), prefixed.next()) {
+                (Some(task), None) if reference.len() >= 6 => Some(task),
+                _ => None,
+            }
+        })
+        .copied()
+}
+
+/// Map each blocked task's id to the titles of the open tasks it depends on.
+/// Dependencies that don't resolve to an open task are treated as done.
+pub fn blocked_by(tasks: &[&Task]) -> HashMap<String, Vec<String>> {
+    tasks
+        .iter()
+        .filter_map(|task| {
+            let blockers: Vec<String> = dependency_refs(task)
+                .into_iter()
+                .filter_map(|reference| resolve(reference, tasks))
+                .filter(|dependency| dependency.id != task.id)
+                .map(|dependency| dependency.title.clone())
+                .collect();
+            (!blockers.is_empty()).then(|| (task.id.clone(), blockers))
+        })
+        .collect()
+}
@@ -0,0 +1,373 @@
+use chrono::{DateTime, Days, Local, NaiveDate};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::cache::Cache;
+use crate::dates::parse_api_datetime;
+use crate::i18n::{self, text};
+use crate::types::Task;
+
+/// Number of days after today listed in the upcoming section
+const UPCOMING_DAYS: u64 = 3;
+
+/// A task as it appears in the digest
+pub struct DigestTask {
+    pub title: String,
+    pub project: String,
+    pub due: DateTime<Local>,
+    pub is_all_day: bool,
+    pub priority: i32,
+}
+
+impl DigestTask {
+    fn time_label(&self) -> Option<String> {
+        (!self.is_all_day).then(|| self.due.format("%H:%M").to_string())
+    }
+
+    /// One-line plain text summary: date (optional), time, title, project and priority
+    pub fn line(&self, show_date: bool) -> String {
+        let mut line = String::new();
+        if show_date {
+            line.push_str(&(text().short_date)(self.due.date_naive()));
+            line.push(' ');
+        }
+        if let Some(time) = self.time_label() {
+            line.push_str(&time);
+            line.push(' ');
+        }
+        line.push_str(&self.title);
+        line.push_str(&format!(" ({})", self.project));
+        if let Some(priority) = self.priority_label() {
+            line.push_str(&format!(" [{}]", priority));
+        }
+        line
+    }
+
+    fn priority_label(&self) -> Option<&'static str> {
+        let [high, medium, low] = text().priorities;
+        match self.priority {
+            5 => Some(high),
+            3 => Some(medium),
+            1 => Some(low),
+            _ => None,
+        }
+    }
+}
+
+pub struct DigestStats {
+    pub open: usize,
+    pub blocked: usize,
+    pub completed_yesterday: usize,
+    pub completed_last_week: usize,
+}
+
+/// Daily summary of overdue, due today and upcoming tasks
+pub struct Digest {
+    pub date: NaiveDate,
+    pub overdue: Vec<DigestTask>,
+    pub today: Vec<DigestTask>,
+    pub upcoming: Vec<(NaiveDate, Vec<DigestTask>)>,
+    pub stats: DigestStats,
+}
+
+impl Digest {
+    pub fn build(cache: &Cache, completions: &HashMap<NaiveDate, usize>) -> Self {
+        let today = Local::now().date_naive();
+        let project_names: HashMap<&str, &str> = cache
+            .projects
+            .iter()
+            .map(|project| (project.id.as_str(), project.name.as_str()))
+            .collect();
+
+        let mut overdue = Vec::new();
+        let mut due_today = Vec::new();
+        let mut upcoming: Vec<(NaiveDate, Vec<DigestTask>)> = (1..=UPCOMING_DAYS)
+            .map(|day| (today + Days::new(day), Vec::new()))
+            .collect();
+
+        let tasks: Vec<&Task> = cache.tasks.values().flatten().collect();
+        for task in &tasks {
+            let Some(due) = task.due_date.as_deref().and_then(parse_api_datetime) else {
+                continue;
+            };
+            let project = if task.project_id.starts_with("inbox") {
+                text().inbox
+            } else {
+                project_names
+                    .get(task.project_id.as_str())
+                    .copied()
+                    .unwrap_or(&task.project_id)
+            };
+            let entry = DigestTask {
+                title: task.title.clone(),
+                project: project.to_string(),
+                due,
+                is_all_day: task.is_all_day.unwrap_or(false),
+                priority: task.priority.unwrap_or(0),
+            };
+
+            let due_date = due.date_naive();
+            if due_date < today {
+                overdue.push(entry);
+            } else if due_date == today {
+                due_today.push(entry);
+            } else if let Some((_, day_tasks)) =
+                upcoming.iter_mut().find(|(date, _)| *date == due_date)
+            {
+                day_tasks.push(entry);
+            }
+        }
+
+        overdue.sort_by_key(|task| task.due);
+        due_today.sort_by_key(|task| task.due);
+        for (_, day_tasks) in &mut upcoming {
+            day_tasks.sort_by_key(|task| task.due);
+        }
+
+        let completed_on = |date: NaiveDate| completions.get(&date).copied().unwrap_or(0);
+        let stats = DigestStats {
+            open: tasks.len(),
+            blocked: tasks
+                .iter()
+                .filter(|task| cache.blocked_by.contains_key(&task.id))
+                .count(),
+            completed_yesterday: today.pred_opt().map(completed_on).unwrap_or(0),
+            completed_last_week: (1..=7)
+                .filter_map(|days| today.checked_sub_days(Days::new(days)))
+                .map(completed_on)
+                .sum(),
+        };
+
+        Self {
+            date: today,
+            overdue,
+            today: due_today,
+            upcoming,
+            stats,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        (text().digest_title)(self.date)
+    }
+
+    pub fn stat_lines(&self) -> [(&'static str, usize); 6] {
+        let text = text();
+        [
+            (text.open_tasks, self.stats.open),
+            (text.overdue, self.overdue.len()),
+            (text.due_today, self.today.len()),
+            (text.blocked, self.stats.blocked),
+            (text.completed_yesterday, self.stats.completed_yesterday),
+            (text.completed_last_week, self.stats.completed_last_week),
+        ]
+    }
+
+    /// Plain text rendering, suitable for a terminal or a text/plain email body
+    pub fn to_text(&self) -> String {
+        let text = text();
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self.title());
+
+        let task_line = |out: &mut String, task: &DigestTask, show_date: bool| {
+            let _ = writeln!(out, "  - {}", task.line(show_date));
+        };
+
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "{} ({})",
+            text.overdue.to_uppercase(),
+            self.overdue.len()
+        );
+        if self.overdue.is_empty() {
+            let _ = writeln!(out, "  {}", text.nothing_overdue);
+        }
+        for task in &self.overdue {
+            task_line(&mut out, task, true);
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "{} ({})",
+            text.due_today.to_uppercase(),
+            self.today.len()
+        );
+        if self.today.is_empty() {
+            let _ = writeln!(out, "  {}", text.nothing_due_today);
+        }
+        for task in &self.today {
+            task_line(&mut out, task, false);
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}", text.upcoming.to_uppercase());
+        for (date, tasks) in &self.upcoming {
+            let _ = writeln!(out, "  {}", i18n::day_heading(*date));
+            if tasks.is_empty() {
+                let _ = writeln!(out, "    {}", text.nothing_due);
+            }
+            for task in tasks {
+                out.push_str("  ");
+                task_line(&mut out, task, false);
+            }
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}", text.stats.to_uppercase());
+        for (label, value) in self.stat_lines() {
+            let _ = writeln!(out, "  {}: {}", label, value);
+        }
+
+        out
+    }
+
+    /// Standalone HTML document with inline styles and fixed section anchors for email clients
+    pub fn to_html(&self) -> String {
+        let text = text();
+        let mut out = String::new();
+        let title = self.title();
+
+        let _ = writeln!(out, "<!DOCTYPE html>");
+        let _ = writeln!(out, "<html>");
+        let _ = writeln!(out, "<head>");
+        let _ = writeln!(out, "<meta charset=\"utf-8\">");
+        let _ = writeln!(out, "<title>{}</title>", escape_html(&title));
+        let _ = writeln!(out, "</head>");
+        let _ = writeln!(
+            out,
+            "<body style=\"font-family: Helvetica, Arial, sans-serif; color: #222222;\">"
+        );
+        let _ = writeln!(
+            out,
+            "<h1 style=\"font-size: 20px;\">{}</h1>",
+            escape_html(&title)
+        );
+        let _ = writeln!(
+            out,
+            "<p><a href=\"#overdue\">{} ({})</a> &middot; <a href=\"#today\">{} ({})</a> &middot; <a href=\"#upcoming\">{}</a> &middot; <a href=\"#stats\">{}</a></p>",
+            text.overdue,
+            self.overdue.len(),
+            text.due_today,
+            self.today.len(),
+            text.upcoming,
+            text.stats
+        );
+
+        html_section(
+            &mut out,
+            "overdue",
+            text.overdue,
+            &self.overdue,
+            true,
+            text.nothing_overdue,
+        );
+        html_section(
+            &mut out,
+            "today",
+            text.due_today,
+            &self.today,
+            false,
+            text.nothing_due_today,
+        );
+
+        html_heading(&mut out, "upcoming", text.upcoming);
+        for (date, tasks) in &self.upcoming {
+            let anchor = format!("upcoming-{}", date.format("%Y-%m-%d"));
+            let _ = writeln!(
+                out,
+                "<h3 id=\"{anchor}\" style=\"font-size: 14px;\"><a name=\"{anchor}\"></a>{}</h3>",
+                i18n::day_heading(*date)
+            );
+            html_task_list(&mut out, tasks, false, text.nothing_due);
+        }
+
+        html_heading(&mut out, "stats", text.stats);
+        let _ = writeln!(out, "<table cellpadding=\"4\" cellspacing=\"0\">");
+        for (label, value) in self.stat_lines() {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td style=\"text-align: right;\"><strong>{}</strong></td></tr>",
+                label, value
+            );
+        }
+        let _ = writeln!(out, "</table>");
+
+        let _ = writeln!(out, "</body>");
+        let _ = writeln!(out, "</html>");
+        out
+    }
+}
+
+/// Heading with both `id` and a named anchor, since some email clients only honour one of them
+fn html_heading(out: &mut String, anchor: &str, label: &str) {
+    let _ = writeln!(
+        out,
+        "<h2 id=\"{anchor}\" style=\"font-size: 16px; border-bottom: 1px solid #dddddd;\"><a name=\"{anchor}\"></a>{label}</h2>"
+    );
+}
+
+fn html_section(
+    out: &mut String,
+    anchor: &str,
+    label: &str,
+    tasks: &[DigestTask],
+    show_date: bool,
+    empty: &str,
+) {
+    html_heading(out, anchor, &format!("{} ({})", label, tasks.len()));
+    html_task_list(out, tasks, show_date, empty);
+}
+
+fn html_task_list(out: &mut String, tasks: &[DigestTask], show_date: bool, empty: &str) {
+    if tasks.is_empty() {
+        let _ = writeln!(out, "<p style=\"color: #888888;\">{}</p>", empty);
+        return;
+    }
+
+    let _ = writeln!(out, "<ul>");
+    for task in tasks {
+        let mut when = String::new();
+        if show_date {
+            when.push_str(&(text().short_date)(task.due.date_naive()));
+            when.push(' ');
+        }
+        if let Some(time) = task.time_label() {
+            when.push_str(&time);
+        }
+
+        let mut item = String::new();
+        if !when.trim().is_empty() {
+            item.push_str(&format!(
+                "<span style=\"color: #888888;\">{}</span> ",
+                when.trim()
+            ));
+        }
+        item.push_str(&escape_html(&task.title));
+        item.push_str(&format!(
+            " <span style=\"color: #888888;\">({})</span>",
+            escape_html(&task.project)
+        ));
+        if let Some(priority) = task.priority_label() {
+            let color = match task.priority {
+                5 => "#d0021b",
+                3 => "#f5a623",
+                _ => "#4a90e2",
+            };
+            item.push_str(&format!(
+                " <span style=\"color: {};\">[{}]</span>",
+                color, priority
+            ));
+        }
+        let _ = writeln!(out, "<li>{}</li>", item);
+    }
+    let _ = writeln!(out, "</ul>");
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
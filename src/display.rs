@@ -1,18 +1,82 @@
-use crate::types::Task;
-use chrono::{Local, TimeZone};
+use crate::config::{ProjectOrder, TaskOrder, TimeStyle};
+use crate::dates::{parse_api_datetime, DateWindow};
+use crate::i18n::{self, text};
+use crate::types::{ChecklistItem, Project, Task};
+use crate::urgency::{Urgency, DEFAULT_SOON_MINUTES};
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
 use dtparse::parse;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::{self, BufWriter, StdoutLock, Write};
+use std::sync::OnceLock;
+
+static TIME_STYLE: OnceLock<TimeStyle> = OnceLock::new();
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+/// Lines of content shown per task in lists unless `display.content_lines` says otherwise
+pub const DEFAULT_CONTENT_LINES: usize = 5;
+
+/// Per-run choices about what task output includes and how it's ordered
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub project_order: ProjectOrder,
+    pub task_order: TaskOrder,
+    /// Leave completed checklist items out, keeping only the progress bar
+    pub hide_done_items: bool,
+    /// Lines of content shown per task in lists, or None for all of it
+    pub content_lines: Option<usize>,
+    /// How close a due time has to be to count as due soon
+    pub due_soon: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            project_order: ProjectOrder::default(),
+            task_order: TaskOrder::default(),
+            hide_done_items: false,
+            content_lines: Some(DEFAULT_CONTENT_LINES),
+            due_soon: Duration::minutes(DEFAULT_SOON_MINUTES as i64),
+        }
+    }
+}
+
+static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap());
+
+/// Choose how times are printed for the rest of the run, from `display.time_style`
+pub fn set_time_style(style: TimeStyle) {
+    let _ = TIME_STYLE.set(style);
+}
+
+/// Choose display options for the rest of the run
+pub fn set_options(options: Options) {
+    let _ = OPTIONS.set(options);
+}
+
+fn options() -> Options {
+    OPTIONS.get().copied().unwrap_or_default()
+}
+
+/// Render to a buffered, locked stdout and flush once at the end
+pub fn to_stdout(
+    render: impl FnOnce(&mut BufWriter<StdoutLock>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(io::stdout().lock());
+    render(&mut out)?;
+    out.flush()
+}
 
 /// Convert markdown links [text](url) to ANSI escape sequence links with color and underline
 fn convert_markdown_links(text: &str) -> String {
-    use regex::Regex;
-
-    let re = Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap();
     // ANSI codes: \x1b[4m = underline, \x1b[36m = cyan color, \x1b[0m = reset
-    re.replace_all(
-        text,
-        "\x1b[4m\x1b[36m\x1b]8;;$2\x1b\\$1\x1b]8;;\x1b\\\x1b[0m",
-    )
-    .to_string()
+    MARKDOWN_LINK
+        .replace_all(
+            text,
+            "\x1b[4m\x1b[36m\x1b]8;;$2\x1b\\$1\x1b]8;;\x1b\\\x1b[0m",
+        )
+        .to_string()
 }
 
 /// Get the appropriate emoji for a task priority
@@ -32,40 +96,120 @@ fn format_time(date_str: &str) -> String {
         let local_datetime = Local
             .from_local_datetime(&datetime)
             .single()
-            .unwrap_or_else(|| Local::now());
+            .unwrap_or_else(Local::now);
+
+        match TIME_STYLE.get().copied().unwrap_or_default() {
+            TimeStyle::Absolute => {
+                return format!(
+                    "{} {}",
+                    (text().short_date)(local_datetime.date_naive()),
+                    local_datetime.format(text().time_format)
+                )
+            }
+            TimeStyle::Iso => return local_datetime.format("%Y-%m-%d %H:%M").to_string(),
+            TimeStyle::Relative => {}
+        }
 
         // Format as "Today HH:MM", "Tomorrow HH:MM", or "MMM DD HH:MM"
         let now = Local::now();
         let today = now.date_naive();
         let datetime_date = local_datetime.date_naive();
 
+        let time = local_datetime.format(text().time_format);
         if datetime_date == today {
-            format!("Today {}", local_datetime.format("%I:%M %p"))
+            format!("{} {}", text().today, time)
         } else if datetime_date == today + chrono::Days::new(1) {
-            format!("Tomorrow {}", local_datetime.format("%I:%M %p"))
+            format!("{} {}", text().tomorrow, time)
         } else {
-            local_datetime.format("%b %d %I:%M %p").to_string()
+            format!("{} {}", (text().short_date)(datetime_date), time)
         }
     } else {
-        "Invalid time".to_string()
+        text().invalid_time.to_string()
     }
 }
 
-/// Print a simplified task (without project info since it's grouped by project)
-pub fn print_task_simple(task: &Task) {
-    println!("  {} {}", get_priority_emoji(task.priority), task.title);
+fn is_done(item: &ChecklistItem) -> bool {
+    item.status == 1
+}
+
+/// Compact checklist progress like `▰▰▱▱ 2/4`; long checklists are scaled to ten segments
+fn checklist_progress(items: &[ChecklistItem]) -> Option<String> {
+    const MAX_SEGMENTS: usize = 10;
+
+    if items.is_empty() {
+        return None;
+    }
+    let done = items.iter().filter(|item| is_done(item)).count();
+    let segments = items.len().min(MAX_SEGMENTS);
+    let filled = (done * segments + items.len() / 2) / items.len();
+    Some(format!(
+        "{}{} {}/{}",
+        "▰".repeat(filled),
+        "▱".repeat(segments - filled),
+        done,
+        items.len()
+    ))
+}
+
+/// Render a simplified task (without project info since it's grouped by project), with its
+/// content cut off after the configured number of lines
+pub fn render_task_simple(
+    out: &mut impl Write,
+    task: &Task,
+    blocked_by: &[String],
+) -> io::Result<()> {
+    render_task(out, task, blocked_by, None, options().content_lines)
+}
+
+/// Render a task, numbered with its `position` in a listing when given
+fn render_task(
+    out: &mut impl Write,
+    task: &Task,
+    blocked_by: &[String],
+    position: Option<usize>,
+    content_lines: Option<usize>,
+) -> io::Result<()> {
+    let items = task.items.as_deref().unwrap_or_default();
+    let progress = checklist_progress(items)
+        .map(|progress| format!("  {}", progress))
+        .unwrap_or_default();
+    let number = position
+        .map(|position| format!("{}. ", position))
+        .unwrap_or_default();
+    writeln!(
+        out,
+        "  {}{} {}{}",
+        number,
+        get_priority_emoji(task.priority),
+        task.title,
+        progress
+    )?;
+    writeln!(out, "    🆔 {}", task.id)?;
+
+    if !blocked_by.is_empty() {
+        writeln!(
+            out,
+            "    ⛔ {}: {}",
+            text().blocked_by,
+            blocked_by.join(", ")
+        )?;
+    }
 
     if let Some(content) = &task.content {
         if !content.is_empty() {
             let lines: Vec<&str> = content.lines().collect();
             if lines.len() == 1 {
                 let converted_content = convert_markdown_links(content);
-                println!("    📝 {}", converted_content);
+                writeln!(out, "    📝 {}", converted_content)?;
             } else {
-                println!("    📝 Content:");
-                for line in lines {
+                writeln!(out, "    📝 {}:", text().content)?;
+                let shown = content_lines.unwrap_or(lines.len()).min(lines.len());
+                for line in &lines[..shown] {
                     let converted_line = convert_markdown_links(line);
-                    println!("      {}", converted_line);
+                    writeln!(out, "      {}", converted_line)?;
+                }
+                if shown < lines.len() {
+                    writeln!(out, "      {}", (text().more_lines)(lines.len() - shown))?;
                 }
             }
         }
@@ -73,36 +217,204 @@ pub fn print_task_simple(task: &Task) {
 
     if let Some(desc) = &task.desc {
         if !desc.is_empty() {
-            println!("    📄 {}", desc);
+            writeln!(out, "    📄 {}", desc)?;
         }
     }
 
     if let Some(due_date) = &task.due_date {
-        println!("    ⏰ Due: {}", format_time(due_date));
+        let due = format_time(due_date);
+        let due = match Urgency::of_task(task, options().due_soon) {
+            Some(urgency) => urgency.paint(&due),
+            None => due,
+        };
+        writeln!(out, "    ⏰ {}: {}", text().due, due)?;
     }
 
     if let Some(start_date) = &task.start_date {
-        println!("    🚀 Start: {}", format_time(start_date));
+        writeln!(out, "    🚀 {}: {}", text().start, format_time(start_date))?;
     }
 
     // Show subtasks
-    if let Some(items) = &task.items {
-        if !items.is_empty() {
-            println!("    📋 Subtasks:");
-            for item in items {
-                let status_icon = if item.status == 1 { "✅" } else { "☐" };
-                println!("      {} {}", status_icon, item.title);
+    let shown: Vec<&ChecklistItem> = items
+        .iter()
+        .filter(|item| !(options().hide_done_items && is_done(item)))
+        .collect();
+    if !shown.is_empty() {
+        writeln!(out, "    📋 {}:", text().subtasks)?;
+        for item in shown {
+            let status_icon = if is_done(item) { "✅" } else { "☐" };
+            writeln!(out, "      {} {}", status_icon, item.title)?;
+        }
+    }
+    Ok(())
+}
+
+/// Format a byte count as a human readable size
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Render every detail of a single task, including fields the list views leave out
+pub fn render_task_details(
+    out: &mut impl Write,
+    task: &Task,
+    project_name: &str,
+    blocked_by: &[String],
+) -> io::Result<()> {
+    render_task(out, task, blocked_by, None, None)?;
+    writeln!(out, "    📁 {}: {}", text().project, project_name)?;
+
+    if let Some(tags) = task.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+        writeln!(out, "    🏷️ {}: {}", text().tags, tags.join(" "))?;
+    }
+
+    if let Some(repeat_flag) = &task.repeat_flag {
+        writeln!(out, "    🔁 {}: {}", text().repeats, repeat_flag)?;
+    }
+
+    if let Some(reminders) = task.reminders.as_ref().filter(|r| !r.is_empty()) {
+        writeln!(out, "    🔔 {}: {}", text().reminders, reminders.join(", "))?;
+    }
+
+    if let Some(comment_count) = task.comment_count.filter(|count| *count > 0) {
+        writeln!(out, "    💬 {}", (text().comments)(comment_count))?;
+    }
+
+    if let Some(attachments) = task.attachments.as_ref().filter(|a| !a.is_empty()) {
+        writeln!(out, "    📎 {}:", text().attachments)?;
+        for (index, attachment) in attachments.iter().enumerate() {
+            writeln!(
+                out,
+                "      {}. {}{}",
+                index + 1,
+                attachment.file_name.as_deref().unwrap_or(text().unnamed),
+                attachment
+                    .size
+                    .map(|size| format!(" ({})", format_size(size)))
+                    .unwrap_or_default()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Sort project ids into sections: Inbox, then (in app order) projects in folders, then the
+/// rest, with projects that aren't known last
+fn sort_projects(project_ids: &mut [String], projects: &[Project], order: ProjectOrder) {
+    let by_id: HashMap<&str, &Project> = projects.iter().map(|p| (p.id.as_str(), p)).collect();
+
+    // The API doesn't expose folder order, so each folder sits where its first project would
+    let mut folder_rank: HashMap<&str, i64> = HashMap::new();
+    for project in projects {
+        if let Some(group_id) = &project.group_id {
+            let rank = folder_rank.entry(group_id).or_insert(i64::MAX);
+            *rank = (*rank).min(project.sort_order.unwrap_or(i64::MAX));
+        }
+    }
+
+    project_ids.sort_by_cached_key(|id| {
+        let project = by_id.get(id.as_str());
+        let name = project.map(|p| p.name.to_lowercase()).unwrap_or_default();
+        match (id.starts_with("inbox"), project, order) {
+            (true, _, _) => (0, 0, String::new(), 0, name),
+            (false, None, _) => (3, 0, String::new(), 0, name),
+            (false, Some(project), ProjectOrder::App) => {
+                let sort_order = project.sort_order.unwrap_or(i64::MAX);
+                match project.group_id.as_deref() {
+                    Some(group_id) => (
+                        1,
+                        folder_rank[group_id],
+                        group_id.to_string(),
+                        sort_order,
+                        name,
+                    ),
+                    None => (2, 0, String::new(), sort_order, name),
+                }
             }
+            (false, Some(_), ProjectOrder::Name) => (2, 0, String::new(), 0, name),
+        }
+    });
+}
+
+/// Sort a project's tasks; sorting is stable, so ties keep the order the API returned
+fn sort_tasks(tasks: &mut [&Task], order: TaskOrder) {
+    let sort_order = |task: &Task| task.sort_order.unwrap_or(i64::MAX);
+    match order {
+        TaskOrder::App => tasks.sort_by_key(|task| sort_order(task)),
+        TaskOrder::Priority => {
+            tasks.sort_by_key(|task| (Reverse(task.priority.unwrap_or(0)), sort_order(task)))
         }
+        TaskOrder::Due => tasks.sort_by_cached_key(|task| {
+            let due = task.due_date.as_deref().and_then(parse_api_datetime);
+            (due.is_none(), due, sort_order(task))
+        }),
+        TaskOrder::Title => tasks.sort_by_cached_key(|task| task.title.to_lowercase()),
     }
 }
 
-/// Print tasks grouped by project
-pub fn print_tasks_grouped(
+/// Red, green and blue of a `#RRGGBB` color, the form the API uses
+fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |range| u8::from_str_radix(&hex[range], 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+/// ANSI foreground code for a color; terminals that don't advertise 24-bit color through
+/// COLORTERM get the nearest of the 256-color cube
+fn foreground_code((red, green, blue): (u8, u8, u8)) -> String {
+    let truecolor = std::env::var("COLORTERM")
+        .map(|value| matches!(value.as_str(), "truecolor" | "24bit"))
+        .unwrap_or(false);
+    if truecolor {
+        return format!("\x1b[38;2;{};{};{}m", red, green, blue);
+    }
+
+    // The cube's six levels per channel sit at 0, 95, 135, 175, 215 and 255
+    let level = |value: u8| match value {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (u16::from(value) - 35) / 40,
+    };
+    format!(
+        "\x1b[38;5;{}m",
+        16 + 36 * level(red) + 6 * level(green) + level(blue)
+    )
+}
+
+/// A block in the project's own color, as in the TickTick app, when it has one
+pub fn project_swatch(project: &Project) -> Option<String> {
+    let color = parse_hex_color(project.color.as_deref()?)?;
+    Some(format!("{}██\x1b[0m", foreground_code(color)))
+}
+
+/// Render tasks grouped by project and numbered from 1, returning their ids in that order
+pub fn render_tasks_grouped(
+    out: &mut impl Write,
     tasks: &[Task],
-    project_map: &std::collections::HashMap<String, String>,
-) {
-    use std::collections::HashMap;
+    projects: &[Project],
+    blocked_by: &HashMap<String, Vec<String>>,
+) -> io::Result<Vec<String>> {
+    let Options {
+        project_order,
+        task_order,
+        ..
+    } = options();
 
     // Group tasks by project
     let mut grouped_tasks: HashMap<String, Vec<&Task>> = HashMap::new();
@@ -110,49 +422,270 @@ pub fn print_tasks_grouped(
     for task in tasks {
         grouped_tasks
             .entry(task.project_id.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(task);
     }
 
-    // Sort project IDs to ensure consistent ordering, with inbox first
     let mut project_ids: Vec<String> = grouped_tasks.keys().cloned().collect();
-    project_ids.sort_by(|a, b| {
-        match (a.starts_with("inbox"), b.starts_with("inbox")) {
-            (true, false) => std::cmp::Ordering::Less, // inbox comes first
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.cmp(b), // alphabetical for the rest
-        }
-    });
+    sort_projects(&mut project_ids, projects, project_order);
 
-    // Print each project's tasks
+    // Print each project's tasks, numbering them across projects
+    let mut shown = Vec::new();
     for (i, project_id) in project_ids.iter().enumerate() {
         if i > 0 {
-            println!(); // Add spacing between project sections
+            writeln!(out)?; // Add spacing between project sections
         }
 
+        let project = projects.iter().find(|project| &project.id == project_id);
         let project_name = if project_id.starts_with("inbox") {
-            "📥 Inbox"
+            format!("📥 {}", text().inbox)
         } else {
-            project_map
-                .get(project_id)
-                .map(|s| s.as_str())
-                .unwrap_or("Unknown Project")
+            project
+                .map(|project| project.name.clone())
+                .unwrap_or_else(|| text().unknown_project.to_string())
         };
+        let icon = project
+            .and_then(project_swatch)
+            .unwrap_or_else(|| "📁".to_string());
 
-        let project_tasks = &grouped_tasks[project_id];
+        let project_tasks = grouped_tasks.get_mut(project_id).unwrap();
+        sort_tasks(project_tasks, task_order);
 
-        println!("╔══════════════════════════════════════════════════");
-        println!(
-            "║ 📁 {} ({} task{})",
+        writeln!(out, "╔══════════════════════════════════════════════════")?;
+        writeln!(
+            out,
+            "║ {} {} ({})",
+            icon,
             project_name,
-            project_tasks.len(),
-            if project_tasks.len() == 1 { "" } else { "s" }
-        );
-        println!("╚══════════════════════════════════════════════════");
-
-        for task in project_tasks {
-            print_task_simple(task);
-            println!();
+            (text().task_count)(project_tasks.len())
+        )?;
+        writeln!(out, "╚══════════════════════════════════════════════════")?;
+
+        for task in project_tasks.iter() {
+            let blockers = blocked_by.get(&task.id).map(Vec::as_slice).unwrap_or(&[]);
+            shown.push(task.id.clone());
+            render_task(
+                out,
+                task,
+                blockers,
+                Some(shown.len()),
+                options().content_lines,
+            )?;
+            writeln!(out)?;
+        }
+    }
+    Ok(shown)
+}
+
+/// Approximate terminal display width, counting emoji and pictographs as two columns
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            '\u{FE0F}' | '\u{200D}' => 0,
+            '\u{2600}'..='\u{27BF}' | '\u{1F000}'..='\u{1FAFF}' => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Truncate text to a display width with an ellipsis and pad it with spaces to exactly that width
+fn fit_to_width(text: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut used = 0;
+
+    if display_width(text) > width {
+        for c in text.chars() {
+            let char_width = display_width(c.encode_utf8(&mut [0; 4]));
+            if used + char_width + 1 > width {
+                break;
+            }
+            fitted.push(c);
+            used += char_width;
         }
+        fitted.push('…');
+        used += 1;
+    } else {
+        fitted.push_str(text);
+        used = display_width(text);
+    }
+
+    fitted.push_str(&" ".repeat(width.saturating_sub(used)));
+    fitted
+}
+
+/// One line of a calendar cell: priority, time (for timed tasks) and title
+fn week_cell_line(task: &Task) -> String {
+    let time = task
+        .due_date
+        .as_deref()
+        .and_then(parse_api_datetime)
+        .filter(|_| task.is_all_day != Some(true))
+        .map(|due| format!("{} ", due.format("%H:%M")))
+        .unwrap_or_default();
+    format!(
+        " {} {}{}",
+        get_priority_emoji(task.priority),
+        time,
+        task.title
+    )
+}
+
+/// Render a 7-column grid with one column per day, tasks stacked inside each day.
+/// Overdue tasks from before the week are listed at the top of the first column.
+pub fn render_week_grid(
+    out: &mut impl Write,
+    days: &[(NaiveDate, Vec<&Task>)],
+    overdue: &[&Task],
+) -> io::Result<()> {
+    let terminal_width = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse::<usize>().ok())
+        .unwrap_or(140);
+    let column_width = (terminal_width.saturating_sub(days.len() + 1) / days.len().max(1)).max(12);
+    let today = Local::now().date_naive();
+
+    let border = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = days.iter().map(|_| "─".repeat(column_width)).collect();
+        format!("{}{}{}", left, segments.join(middle), right)
+    };
+
+    // Stack each day's lines, with overdue spillover leading the first column. Lines are
+    // colored by urgency only once fitted, so the escape codes don't count towards the width.
+    let due_soon = options().due_soon;
+    let cell = |task: &Task| (week_cell_line(task), Urgency::of_task(task, due_soon));
+    let mut columns: Vec<Vec<(String, Option<Urgency>)>> = days
+        .iter()
+        .map(|(_, tasks)| tasks.iter().map(|task| cell(task)).collect())
+        .collect();
+    if let Some(first) = columns.first_mut() {
+        let spillover = overdue.iter().map(|task| {
+            let (line, urgency) = cell(task);
+            (format!(" ⚠️{}", line), urgency)
+        });
+        first.splice(0..0, spillover);
+    }
+    let rows = columns.iter().map(|lines| lines.len()).max().unwrap_or(0);
+
+    writeln!(out, "{}", border("┌", "┬", "┐"))?;
+    let headers: Vec<String> = days
+        .iter()
+        .map(|(date, _)| {
+            let label = format!(
+                " {} {}",
+                text().weekdays[date.weekday().num_days_from_monday() as usize],
+                (text().short_date)(*date)
+            );
+            let label = fit_to_width(&label, column_width);
+            if *date == today {
+                // ANSI codes: \x1b[1m = bold, \x1b[7m = reverse video
+                format!("\x1b[1m\x1b[7m{}\x1b[0m", label)
+            } else {
+                label
+            }
+        })
+        .collect();
+    writeln!(out, "│{}│", headers.join("│"))?;
+    writeln!(out, "{}", border("├", "┼", "┤"))?;
+
+    for row in 0..rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|lines| match lines.get(row) {
+                Some((line, Some(urgency))) => urgency.paint(&fit_to_width(line, column_width)),
+                Some((line, None)) => fit_to_width(line, column_width),
+                None => " ".repeat(column_width),
+            })
+            .collect();
+        writeln!(out, "│{}│", cells.join("│"))?;
     }
+    if rows == 0 {
+        let cells: Vec<String> = days.iter().map(|_| " ".repeat(column_width)).collect();
+        writeln!(out, "│{}│", cells.join("│"))?;
+    }
+    writeln!(out, "{}", border("└", "┴", "┘"))
+}
+
+/// 256-color background for a heatmap cell; green for completions, orange-red for tasks due
+fn heat_color(count: usize, completed: bool) -> Option<u8> {
+    let level = match count {
+        0 => return None,
+        1 => 0,
+        2 => 1,
+        3..=4 => 2,
+        _ => 3,
+    };
+    let palette: [u8; 4] = if completed {
+        [22, 28, 34, 40]
+    } else {
+        [94, 130, 166, 202]
+    };
+    Some(palette[level])
+}
+
+/// Render a calendar for the month with each day shaded by its task count
+pub fn render_month_heatmap(
+    out: &mut impl Write,
+    month_start: NaiveDate,
+    counts: &HashMap<NaiveDate, usize>,
+    completed: bool,
+) -> io::Result<()> {
+    let today = Local::now().date_naive();
+    let days: Vec<NaiveDate> = month_start
+        .iter_days()
+        .take_while(|date| date.month() == month_start.month())
+        .collect();
+
+    writeln!(
+        out,
+        "📆 {}",
+        (text().heatmap_title)(&(text().month_year)(month_start), completed)
+    )?;
+    writeln!(out)?;
+    let weekdays: Vec<String> = i18n::weekdays_in_order()
+        .iter()
+        .map(|weekday| format!("{:>4}", weekday))
+        .collect();
+    writeln!(out, "{}", weekdays.join(" "))?;
+
+    let first_day = i18n::first_day();
+    let leading = DateWindow::week(month_start, first_day).start;
+    let mut line = "     ".repeat((month_start - leading).num_days() as usize);
+    for date in &days {
+        let count = counts.get(date).copied().unwrap_or(0);
+        // ANSI codes: \x1b[48;5;Nm = 256-color background, \x1b[4m = underline for today
+        let style = heat_color(count, completed)
+            .map(|color| format!("\x1b[48;5;{}m\x1b[97m", color))
+            .unwrap_or_default();
+        let today_style = if *date == today { "\x1b[1m\x1b[4m" } else { "" };
+        line.push_str(&format!(
+            "{}{} {:>2} \x1b[0m ",
+            style,
+            today_style,
+            date.day()
+        ));
+
+        if date.succ_opt().map(|next| next.weekday()) == Some(first_day) {
+            writeln!(out, "{}", line.trim_end())?;
+            line.clear();
+        }
+    }
+    if !line.is_empty() {
+        writeln!(out, "{}", line.trim_end())?;
+    }
+
+    let legend: String = [1, 2, 3, 5]
+        .iter()
+        .filter_map(|count| heat_color(*count, completed))
+        .map(|color| format!("\x1b[48;5;{}m  \x1b[0m ", color))
+        .collect();
+    writeln!(out)?;
+    writeln!(out, "{} {}{}", text().less, legend, text().more)?;
+
+    let total: usize = days.iter().filter_map(|date| counts.get(date)).sum();
+    let busiest = days
+        .iter()
+        .filter_map(|date| counts.get(date).map(|count| (*count, *date)))
+        .max();
+    let busiest = busiest.map(|(count, date)| ((text().short_date)(date), count));
+    writeln!(out, "{}", (text().month_total)(total, busiest))
 }
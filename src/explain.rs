@@ -0,0 +1,241 @@
+use chrono::{Local, NaiveDate, NaiveTime};
+use dtparse::parse;
+use std::cmp::Ordering;
+
+use crate::dates::{parse_api_datetime, DateWindow};
+use crate::types::Task;
+
+/// The rule `tick today` uses to pick tasks, kept apart from the listing so `tick explain`
+/// reports exactly the same decision. Repeat rules aren't expanded and there are no
+/// include/exclude rules, so only the status and the current occurrence's dates count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TodayCheck {
+    pub open: bool,
+    /// Where the due date falls relative to today; None without a usable one
+    pub due: Option<Ordering>,
+    /// Where the start date falls relative to today; None without a usable one
+    pub start: Option<Ordering>,
+}
+
+impl TodayCheck {
+    /// Check a task's status and its due and start dates, already on the local calendar
+    pub fn classify(
+        status: i32,
+        due: Option<NaiveDate>,
+        start: Option<NaiveDate>,
+        today: DateWindow,
+    ) -> Self {
+        TodayCheck {
+            open: status == 0,
+            due: due.map(|day| today.compare(day)),
+            start: start.map(|day| today.compare(day)),
+        }
+    }
+
+    pub fn of_task(task: &Task, today: DateWindow) -> Self {
+        let local_day = |raw: Option<&str>| {
+            raw.and_then(parse_api_datetime)
+                .map(|datetime| datetime.date_naive())
+        };
+        Self::classify(
+            task.status,
+            local_day(task.due_date.as_deref()),
+            local_day(task.start_date.as_deref()),
+            today,
+        )
+    }
+
+    /// Overdue, due today or starting today
+    pub fn dated(&self) -> bool {
+        matches!(self.due, Some(Ordering::Less | Ordering::Equal))
+            || self.start == Some(Ordering::Equal)
+    }
+
+    pub fn shown(&self) -> bool {
+        self.open && self.dated()
+    }
+
+    /// Why the task is left out, or None if it's shown
+    pub fn reason(&self) -> Option<&'static str> {
+        if !self.open {
+            Some("it's completed")
+        } else if !self.dated() {
+            Some("it's neither due by today nor starting today")
+        } else {
+            None
+        }
+    }
+}
+
+/// The steps from a raw API date to the local day it falls on
+fn date_steps(lines: &mut Vec<String>, task: &Task, raw: &str) {
+    lines.push(format!("  raw value   {}", raw));
+    let Ok((naive, offset)) = parse(raw) else {
+        lines.push("  ❌ Can't be parsed, so it's ignored".to_string());
+        return;
+    };
+    match offset {
+        Some(offset) => lines.push(format!(
+            "  parsed      {} at UTC{}",
+            naive.format("%Y-%m-%d %H:%M"),
+            offset
+        )),
+        None => lines.push(format!(
+            "  parsed      {} without a UTC offset, so it's taken as local time",
+            naive.format("%Y-%m-%d %H:%M")
+        )),
+    }
+
+    let Some(local) = parse_api_datetime(raw) else {
+        lines.push("  ❌ That time doesn't exist in your time zone, so it's ignored".to_string());
+        return;
+    };
+    lines.push(format!(
+        "  local time  {} (UTC{})",
+        local.format("%Y-%m-%d %H:%M"),
+        local.offset()
+    ));
+
+    if task.is_all_day == Some(true) {
+        lines.push("  all-day     yes, only the local day counts".to_string());
+        // All-day dates are stored as midnight in the task's own time zone
+        if local.time() != NaiveTime::MIN {
+            lines.push(format!(
+                "  ⚠️ All-day dates are midnight in the task's time zone ({}), which is {} here, \
+                 so the day can differ from the one the app shows",
+                task.time_zone.as_deref().unwrap_or("unknown"),
+                local.format("%H:%M")
+            ));
+        }
+    }
+}
+
+/// Go through every rule `tick today` applies to a task: its status, due and start dates in
+/// local time, recurrence and the project scope, one line per step. `request_limit` is the
+/// --max-requests budget.
+pub fn explain(
+    task: &Task,
+    project_name: &str,
+    in_scope: bool,
+    request_limit: Option<usize>,
+) -> Vec<String> {
+    let today = DateWindow::day(Local::now().date_naive());
+    let check = TodayCheck::of_task(task, today);
+    let mut lines = vec![
+        format!("🔍 \"{}\" ({})", task.title, task.id),
+        format!(
+            "   Today is {}, in UTC{}",
+            today.start.format("%Y-%m-%d"),
+            Local::now().offset()
+        ),
+        String::new(),
+    ];
+
+    lines.push("Status".to_string());
+    lines.push(if check.open {
+        "  ✅ Open".to_string()
+    } else {
+        format!(
+            "  ❌ Completed (status {}); only open tasks are shown",
+            task.status
+        )
+    });
+
+    lines.push("Due date".to_string());
+    if let Some(raw) = task.due_date.as_deref() {
+        date_steps(&mut lines, task, raw);
+    }
+    lines.push(match check.due {
+        _ if task.due_date.is_none() => "  ➖ None".to_string(),
+        Some(Ordering::Less) => "  ✅ Overdue".to_string(),
+        Some(Ordering::Equal) => "  ✅ Due today".to_string(),
+        Some(Ordering::Greater) => "  ➖ Due after today".to_string(),
+        None => "  ➖ Not usable".to_string(),
+    });
+
+    lines.push("Start date".to_string());
+    if let Some(raw) = task.start_date.as_deref() {
+        date_steps(&mut lines, task, raw);
+    }
+    lines.push(match check.start {
+        _ if task.start_date.is_none() => "  ➖ None".to_string(),
+        Some(Ordering::Equal) => "  ✅ Starts today".to_string(),
+        Some(_) => "  ➖ Doesn't start today; only a start date of today counts".to_string(),
+        None => "  ➖ Not usable".to_string(),
+    });
+
+    lines.push("Repeat".to_string());
+    match task.repeat_flag.as_deref().filter(|rule| !rule.is_empty()) {
+        Some(rule) => {
+            lines.push(format!("  🔁 {}", rule));
+            lines.push(
+                "  tick doesn't expand repeats: only the current occurrence's dates above count, \
+                 and the next one appears once this one is completed"
+                    .to_string(),
+            );
+        }
+        None => lines.push("  ➖ Doesn't repeat".to_string()),
+    }
+
+    lines.push("Project".to_string());
+    lines.push(if in_scope {
+        format!("  ✅ {} is fetched", project_name)
+    } else {
+        format!(
+            "  ❌ {} is outside the project scope (--project-scope or sync.project_scope)",
+            project_name
+        )
+    });
+    if let Some(limit) = request_limit {
+        lines.push(format!(
+            "  ⚠️ With a budget of {} request(s), projects are skipped once it runs out",
+            limit
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(match check.reason() {
+        None if in_scope => "✅ Shown by `tick today`".to_string(),
+        None => "❌ Not shown by `tick today`: its project isn't fetched".to_string(),
+        Some(reason) => format!("❌ Not shown by `tick today`: {}", reason),
+    });
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, d).unwrap()
+    }
+
+    fn check(status: i32, due: Option<u32>, start: Option<u32>) -> TodayCheck {
+        TodayCheck::classify(
+            status,
+            due.map(day),
+            start.map(day),
+            DateWindow::day(day(10)),
+        )
+    }
+
+    #[test]
+    fn shows_open_tasks_overdue_due_or_starting_today() {
+        assert!(check(0, Some(9), None).shown());
+        assert!(check(0, Some(10), None).shown());
+        assert!(check(0, None, Some(10)).shown());
+        assert!(check(0, Some(11), Some(10)).shown());
+        assert_eq!(check(0, Some(10), None).reason(), None);
+    }
+
+    #[test]
+    fn leaves_out_later_undated_and_completed_tasks() {
+        let later = "it's neither due by today nor starting today";
+        assert_eq!(check(0, Some(11), None).reason(), Some(later));
+        assert_eq!(check(0, None, None).reason(), Some(later));
+        // Only a start date of today counts, not an earlier one
+        assert_eq!(check(0, None, Some(9)).reason(), Some(later));
+        assert_eq!(check(2, Some(10), None).reason(), Some("it's completed"));
+    }
+}
@@ -0,0 +1,377 @@
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+use crate::dates::{parse_api_datetime, parse_user_datetime, DateWindow};
+use crate::i18n;
+use crate::types::{Priority, Project, Task};
+
+/// Which due dates a `due:` predicate matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueFilter {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    None,
+    On(NaiveDate),
+}
+
+/// A parsed filter expression such as `project:Work AND (overdue OR priority:high)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Project(String),
+    Tag(String),
+    Priority(Priority),
+    Due(DueFilter),
+    /// Case-insensitive substring of the title, content or description
+    Text(String),
+}
+
+/// Information outside the task itself that predicates need
+pub struct FilterContext {
+    project_names: HashMap<String, String>,
+    today: NaiveDate,
+}
+
+impl FilterContext {
+    pub fn new(projects: &[Project]) -> Self {
+        Self {
+            project_names: projects
+                .iter()
+                .map(|project| (project.id.clone(), project.name.clone()))
+                .collect(),
+            today: Local::now().date_naive(),
+        }
+    }
+
+    fn project_name<'a>(&'a self, task: &'a Task) -> &'a str {
+        if task.project_id.starts_with("inbox") {
+            "Inbox"
+        } else {
+            self.project_names
+                .get(&task.project_id)
+                .map(|name| name.as_str())
+                .unwrap_or(&task.project_id)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Word(String),
+    /// A word with quotes in it, which is always a term, never AND, OR or NOT
+    Quoted(String),
+}
+
+/// Split an expression into words and parentheses; double quotes group words with spaces
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let finish = |tokens: &mut Vec<Token>, word: &mut String, quoted: &mut bool| {
+        if !word.is_empty() {
+            let word = std::mem::take(word);
+            tokens.push(if *quoted {
+                Token::Quoted(word)
+            } else {
+                Token::Word(word)
+            });
+        }
+        *quoted = false;
+    };
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            c if in_quotes => word.push(c),
+            '(' | ')' | ' ' | '\t' => {
+                finish(&mut tokens, &mut word, &mut quoted);
+                match c {
+                    '(' => tokens.push(Token::LParen),
+                    ')' => tokens.push(Token::RParen),
+                    _ => {}
+                }
+            }
+            c => word.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(anyhow!("Unterminated quote in filter"));
+    }
+    finish(&mut tokens, &mut word, &mut quoted);
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_and()?;
+        while self.peek_keyword("OR") {
+            self.next();
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        Ok(filter)
+    }
+
+    /// Adjacent terms without an operator are combined with AND
+    fn parse_and(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_not()?;
+        loop {
+            if self.peek_keyword("AND") {
+                self.next();
+            } else if self.peek().is_none()
+                || self.peek() == Some(&Token::RParen)
+                || self.peek_keyword("OR")
+            {
+                return Ok(filter);
+            }
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_not()?));
+        }
+    }
+
+    fn parse_not(&mut self) -> Result<Filter> {
+        if self.peek_keyword("NOT") {
+            self.next();
+            return Ok(Filter::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Filter> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let filter = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(filter),
+                    _ => Err(anyhow!("Missing closing parenthesis in filter")),
+                }
+            }
+            Some(Token::RParen) => Err(anyhow!("Unexpected ')' in filter")),
+            Some(Token::Word(word) | Token::Quoted(word)) => parse_predicate(&word),
+            None => Err(anyhow!("Filter ended unexpectedly")),
+        }
+    }
+}
+
+fn parse_due(value: &str) -> Result<DueFilter> {
+    Ok(match value.to_lowercase().as_str() {
+        "overdue" => DueFilter::Overdue,
+        "today" => DueFilter::Today,
+        "tomorrow" => DueFilter::Tomorrow,
+        "week" => DueFilter::ThisWeek,
+        "none" => DueFilter::None,
+        _ => DueFilter::On(parse_user_datetime(value)?.0.date_naive()),
+    })
+}
+
+fn parse_predicate(word: &str) -> Result<Filter> {
+    let Some((key, value)) = word.split_once(':') else {
+        return Ok(match word.to_lowercase().as_str() {
+            "overdue" => Filter::Due(DueFilter::Overdue),
+            "today" => Filter::Due(DueFilter::Today),
+            "tomorrow" => Filter::Due(DueFilter::Tomorrow),
+            "nodue" => Filter::Due(DueFilter::None),
+            _ => Filter::Text(word.to_lowercase()),
+        });
+    };
+
+    if value.is_empty() {
+        return Err(anyhow!("Missing value for '{}:' in filter", key));
+    }
+
+    match key.to_lowercase().as_str() {
+        "project" => Ok(Filter::Project(value.to_string())),
+        "tag" => Ok(Filter::Tag(value.trim_start_matches('#').to_string())),
+        "priority" => Priority::from_str(value, true)
+            .map(Filter::Priority)
+            .map_err(|_| anyhow!("Unknown priority '{}' in filter", value)),
+        "due" => Ok(Filter::Due(parse_due(value)?)),
+        "text" => Ok(Filter::Text(value.to_lowercase())),
+        _ => Err(anyhow!("Unknown filter key '{}'", key)),
+    }
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+
+        let filter = parser.parse_or()?;
+        if parser.peek().is_some() {
+            return Err(anyhow!("Unexpected trailing input in filter"));
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, task: &Task, context: &FilterContext) -> bool {
+        match self {
+            Filter::And(left, right) => left.matches(task, context) && right.matches(task, context),
+            Filter::Or(left, right) => left.matches(task, context) || right.matches(task, context),
+            Filter::Not(inner) => !inner.matches(task, context),
+            Filter::Project(name) => context.project_name(task).eq_ignore_ascii_case(name),
+            Filter::Tag(tag) => task
+                .tags
+                .iter()
+                .flatten()
+                .any(|task_tag| task_tag.eq_ignore_ascii_case(tag)),
+            Filter::Priority(priority) => task.priority.unwrap_or(0) == priority.value(),
+            Filter::Due(due) => {
+                let due_date = task
+                    .due_date
+                    .as_deref()
+                    .and_then(parse_api_datetime)
+                    .map(|datetime| datetime.date_naive());
+                let today = context.today;
+                match (due, due_date) {
+                    (DueFilter::None, due_date) => due_date.is_none(),
+                    (_, None) => false,
+                    (DueFilter::Overdue, Some(date)) => date < today,
+                    (DueFilter::Today, Some(date)) => date == today,
+                    (DueFilter::Tomorrow, Some(date)) => date == today.succ_opt().unwrap_or(today),
+                    (DueFilter::ThisWeek, Some(date)) => {
+                        DateWindow::week(today, i18n::first_day()).contains(date)
+                    }
+                    (DueFilter::On(on), Some(date)) => date == *on,
+                }
+            }
+            Filter::Text(text) => std::iter::once(task.title.as_str())
+                .chain(task.content.as_deref())
+                .chain(task.desc.as_deref())
+                .any(|field| field.to_lowercase().contains(text.as_str())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(word: &str) -> Filter {
+        Filter::Text(word.to_string())
+    }
+
+    fn and(left: Filter, right: Filter) -> Filter {
+        Filter::And(Box::new(left), Box::new(right))
+    }
+
+    fn or(left: Filter, right: Filter) -> Filter {
+        Filter::Or(Box::new(left), Box::new(right))
+    }
+
+    fn not(inner: Filter) -> Filter {
+        Filter::Not(Box::new(inner))
+    }
+
+    fn error(input: &str) -> String {
+        Filter::parse(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            Filter::parse("a OR b AND c").unwrap(),
+            or(text("a"), and(text("b"), text("c")))
+        );
+        assert_eq!(
+            Filter::parse("a b or c").unwrap(),
+            or(and(text("a"), text("b")), text("c"))
+        );
+        assert_eq!(
+            Filter::parse("(a OR b) c").unwrap(),
+            and(or(text("a"), text("b")), text("c"))
+        );
+    }
+
+    #[test]
+    fn not_applies_to_the_next_term() {
+        assert_eq!(
+            Filter::parse("NOT a b").unwrap(),
+            and(not(text("a")), text("b"))
+        );
+        assert_eq!(
+            Filter::parse("not not (a or b)").unwrap(),
+            not(not(or(text("a"), text("b"))))
+        );
+    }
+
+    #[test]
+    fn predicates() {
+        assert_eq!(
+            Filter::parse("tag:#home priority:high overdue").unwrap(),
+            and(
+                and(
+                    Filter::Tag("home".to_string()),
+                    Filter::Priority(Priority::High)
+                ),
+                Filter::Due(DueFilter::Overdue)
+            )
+        );
+    }
+
+    #[test]
+    fn quotes_group_words_and_are_never_operators() {
+        assert_eq!(
+            Filter::parse("project:\"My Work\"").unwrap(),
+            Filter::Project("My Work".to_string())
+        );
+        assert_eq!(
+            Filter::parse("\"call (bank)\"").unwrap(),
+            text("call (bank)")
+        );
+        assert_eq!(Filter::parse("\"AND\"").unwrap(), text("and"));
+        assert_eq!(
+            Filter::parse("a \"or\" b").unwrap(),
+            and(and(text("a"), text("or")), text("b"))
+        );
+        assert_eq!(Filter::parse("NOT \"not\"").unwrap(), not(text("not")));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("\"open"), "Unterminated quote in filter");
+        assert_eq!(error("(a OR b"), "Missing closing parenthesis in filter");
+        assert_eq!(error(")"), "Unexpected ')' in filter");
+        assert_eq!(error("a)"), "Unexpected trailing input in filter");
+        assert_eq!(error("a AND"), "Filter ended unexpectedly");
+        assert_eq!(error(""), "Filter ended unexpectedly");
+        assert_eq!(error("tag:"), "Missing value for 'tag:' in filter");
+        assert_eq!(error("colour:red"), "Unknown filter key 'colour'");
+        assert_eq!(
+            error("priority:urgent"),
+            "Unknown priority 'urgent' in filter"
+        );
+    }
+}
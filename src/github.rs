@@ -0,0 +1,169 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::{header, Client};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::fs;
+
+use crate::paths;
+use crate::types::Task;
+
+/// Environment variable checked for a token before `github.token`
+pub const TOKEN_ENV: &str = "GITHUB_TOKEN";
+
+/// Keyring entry holding `github.token` when the tokens are kept in the keyring
+pub const TOKEN_SECRET: &str = "github_token";
+
+/// Pages of 100 issues fetched at most, to bound the number of requests
+const MAX_PAGES: usize = 10;
+
+#[derive(Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct ApiLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ApiMilestone {
+    due_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct ApiIssue {
+    number: u64,
+    title: String,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    labels: Vec<ApiLabel>,
+    milestone: Option<ApiMilestone>,
+    /// Present when the "issue" is a pull request
+    pull_request: Option<serde_json::Value>,
+}
+
+/// An issue as it's mirrored into a task
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub closed: bool,
+    /// Labels in tag form: lowercase, with spaces replaced by dashes
+    pub tags: Vec<String>,
+    /// Due date of the issue's milestone
+    pub due: Option<NaiveDate>,
+}
+
+pub struct GitHub {
+    client: Client,
+    token: String,
+}
+
+impl GitHub {
+    pub fn new(token: String) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!("tick/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self { client, token })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(url)
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.token))
+            .header(header::ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await?;
+            return Err(anyhow!(
+                "GitHub request failed ({}): {}",
+                status,
+                error_text
+            ));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Login of the user the token belongs to
+    pub async fn login(&self) -> Result<String> {
+        let user: ApiUser = self.get("https://api.github.com/user").await?;
+        Ok(user.login)
+    }
+
+    /// Open and closed issues in `repo` assigned to `login`, leaving out pull requests
+    pub async fn assigned_issues(&self, repo: &str, login: &str) -> Result<Vec<Issue>> {
+        let mut issues = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let url = format!(
+                "https://api.github.com/repos/{}/issues?assignee={}&state=all&per_page=100&page={}",
+                repo, login, page
+            );
+            let batch: Vec<ApiIssue> = self.get(&url).await?;
+            let last_page = batch.len() < 100;
+
+            issues.extend(
+                batch
+                    .into_iter()
+                    .filter(|issue| issue.pull_request.is_none())
+                    .map(|issue| Issue {
+                        number: issue.number,
+                        title: issue.title,
+                        url: issue.html_url,
+                        closed: issue.state == "closed",
+                        tags: issue
+                            .labels
+                            .iter()
+                            .map(|label| label.name.to_lowercase().replace(' ', "-"))
+                            .collect(),
+                        due: issue
+                            .milestone
+                            .and_then(|milestone| milestone.due_on)
+                            .map(|due_on| due_on.date_naive()),
+                    }),
+            );
+            if last_page {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+}
+
+/// Line in a task's content that ties it to an issue, e.g. `github: owner/name#12`
+pub fn metadata_line(repo: &str, number: u64) -> String {
+    format!("github: {}#{}", repo, number)
+}
+
+/// The `owner/name#12` key of the issue a task mirrors, if any
+pub fn issue_key(task: &Task) -> Option<&str> {
+    task.content
+        .as_deref()?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("github: "))
+}
+
+/// Keys of every issue a task was ever created for, so issues whose task was completed or
+/// deleted in TickTick aren't mirrored again
+pub fn load_mirrored() -> Result<BTreeSet<String>> {
+    let path = paths::github_sync_file()?;
+    if !path.exists() {
+        return Ok(BTreeSet::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn save_mirrored(mirrored: &BTreeSet<String>) -> Result<()> {
+    let path = paths::github_sync_file()?;
+    paths::write_atomic(&path, &serde_json::to_string_pretty(mirrored)?)
+}
@@ -0,0 +1,107 @@
+use regex::Regex;
+
+/// A line of a task field, numbered from 1, and whether it matched
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+    pub is_match: bool,
+}
+
+/// Matching lines with up to `before` and `after` lines of context. Overlapping or adjacent
+/// context is merged, so each block is a run of consecutive lines.
+pub fn context_blocks<'a>(
+    text: &'a str,
+    pattern: &Regex,
+    before: usize,
+    after: usize,
+) -> Vec<Vec<Line<'a>>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let matches: Vec<usize> = (0..lines.len())
+        .filter(|index| pattern.is_match(lines[*index]))
+        .collect();
+
+    let mut blocks: Vec<Vec<Line>> = Vec::new();
+    // One past the last line already added to a block
+    let mut end = 0;
+    for &index in &matches {
+        let start = index.saturating_sub(before).max(end);
+        let stop = (index + after + 1).min(lines.len());
+        if blocks.is_empty() || start > end {
+            blocks.push(Vec::new());
+        }
+
+        let block = blocks.last_mut().unwrap();
+        block.extend((start..stop).map(|index| Line {
+            number: index + 1,
+            text: lines[index],
+            is_match: matches.binary_search(&index).is_ok(),
+        }));
+        end = end.max(stop);
+    }
+    blocks
+}
+
+/// Wrap every match in bold red, like ripgrep
+pub fn highlight(text: &str, pattern: &Regex) -> String {
+    // ANSI codes: \x1b[1m = bold, \x1b[31m = red, \x1b[0m = reset
+    pattern
+        .replace_all(text, "\x1b[1m\x1b[31m$0\x1b[0m")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each block as its line numbers, matches marked with `*`
+    fn blocks(text: &str, pattern: &str, before: usize, after: usize) -> Vec<Vec<String>> {
+        let pattern = Regex::new(pattern).unwrap();
+        context_blocks(text, &pattern, before, after)
+            .iter()
+            .map(|block| {
+                block
+                    .iter()
+                    .map(|line| format!("{}{}", line.number, if line.is_match { "*" } else { "" }))
+                    .collect()
+            })
+            .collect()
+    }
+
+    const TEXT: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight";
+
+    #[test]
+    fn separate_matches_get_separate_blocks() {
+        assert_eq!(
+            blocks(TEXT, "two|seven", 1, 0),
+            [vec!["1", "2*"], vec!["6", "7*"]]
+        );
+        assert!(blocks(TEXT, "nine", 2, 2).is_empty());
+    }
+
+    #[test]
+    fn adjacent_context_is_merged() {
+        // Line five is between the blocks at first, then joins them as context
+        assert_eq!(
+            blocks(TEXT, "three|six", 0, 1),
+            [vec!["3*", "4"], vec!["6*", "7"]]
+        );
+        assert_eq!(
+            blocks(TEXT, "three|six", 1, 1),
+            [vec!["2", "3*", "4", "5", "6*", "7"]]
+        );
+        // Matches on consecutive lines without context
+        assert_eq!(blocks(TEXT, "four|five", 0, 0), [vec!["4*", "5*"]]);
+    }
+
+    #[test]
+    fn overlapping_context_lists_each_line_once() {
+        assert_eq!(
+            blocks(TEXT, "three|four", 2, 2),
+            [vec!["1", "2", "3*", "4*", "5", "6"]]
+        );
+        assert_eq!(
+            blocks(TEXT, "^(one|eight)$", 9, 9),
+            [vec!["1*", "2", "3", "4", "5", "6", "7", "8*"]]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Result};
+use clap::CommandFactory;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::Cli;
+
+/// Shown after `tick --help`
+pub const OVERVIEW: &str = "\
+Run `tick help <command>` for a command's options and examples. The date, quick-add and \
+filter syntax is explained under `tick help add`, `tick help capture` and `tick help list`.";
+
+/// Dates accepted by --due, --from and --to
+pub const DATES: &str = "\
+Dates:
+  2024-05-01          all-day on that date
+  May 1 5pm           a date with a time of day, in local time
+  2024-05-01 17:30    the same in ISO form
+A date without a time of day makes an all-day task. Dates in the past, more than a year
+away, or at midnight although a time was given are shown in full and need confirming;
+--yes skips the question, which scripts without a terminal need to do.
+
+Examples:
+  tick add Pay rent --due 2024-06-01 --priority high
+  tick add \"Call the bank\" -p Finance -d \"May 2 9am\" -c \"Ask about the card\"";
+
+/// Amounts and dates accepted by snooze
+pub const SHIFTS: &str = "\
+When:
+  3h, 1d, 2w          later by hours, days or weeks; overdue tasks count from today
+  today, tomorrow     to that day, keeping the time of day
+  2024-05-01          to that date, keeping the time of day
+  \"May 1\"             any other date, as accepted by --due
+
+--auto instead shows how many tasks are due on each of the next 7 days (--within changes
+the number, up to 365) and offers the least busy one.
+
+Examples:
+  tick snooze 3f2a 1d
+  tick snooze 3f2a --auto --within 14
+  tick bulk -f overdue snooze tomorrow";
+
+/// The quick-add markers used by capture
+pub const QUICK_ADD: &str = "\
+Markers, anywhere in the line:
+  ~Work, ~\"Home Improvement\"    project
+  #phone                         tag, may be repeated
+  !high, !medium, !low, !none    priority
+  @today, @tomorrow, @3d, @2h    due date; whole days make an all-day task
+  @2024-05-01, @\"May 1 5pm\"      any date --due accepts
+Put a backslash before a word to keep it in the title, e.g. \\#1.
+
+Example:
+  › Call the bank about the card ~Finance #phone !high @tomorrow";
+
+/// Filter expressions used by list, blocked, ready and bulk
+pub const FILTERS: &str = "\
+Filter expressions combine predicates with AND, OR, NOT and parentheses. Terms next to each
+other are combined with AND. NOT binds tightest, then AND, then OR. Quote values that contain
+spaces; a quoted word such as \"and\" is always a term, never an operator.
+  project:Work                   in the project Work (project:Inbox for the inbox)
+  tag:errands                    tagged errands
+  priority:high                  high, medium, low or none
+  due:today, due:tomorrow        due that day
+  due:week, due:none             due this week, or without a due date
+  due:2024-05-01                 due on that date
+  overdue, today, tomorrow, nodue    shorthands for the due: forms
+  text:invoice, or just invoice  title, content or description contains the text
+
+Examples:
+  tick list -f \"project:Work AND due:week\"
+  tick list -f 'tag:errands AND NOT project:\"Side Project\"'
+  tick bulk -f \"priority:none AND overdue\" set-priority low";
+
+/// Render a man page for `command` as `<name>.1`, then one for each subcommand as
+/// `<name>-<subcommand>.1`
+fn write_pages(
+    dir: &Path,
+    command: clap::Command,
+    name: String,
+    written: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let subcommands: Vec<clap::Command> = command.get_subcommands().cloned().collect();
+
+    let path = dir.join(format!("{}.1", name));
+    let mut page = Vec::new();
+    clap_mangen::Man::new(command.name(name.clone())).render(&mut page)?;
+    fs::write(&path, page).map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+    written.push(path);
+
+    for subcommand in subcommands {
+        let subcommand_name = format!("{}-{}", name, subcommand.get_name());
+        write_pages(dir, subcommand, subcommand_name, written)?;
+    }
+    Ok(())
+}
+
+/// Write man pages for tick and every subcommand into `dir`, returning their paths
+pub fn write_man_pages(dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+
+    let mut written = Vec::new();
+    write_pages(dir, Cli::command(), "tick".to_string(), &mut written)?;
+    Ok(written)
+}
@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::paths;
+use crate::types::Task;
+
+/// A task that was completed, either through tick or detected as closed during a sync
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Completion {
+    pub completed_at: DateTime<Local>,
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+    /// True when found by a sync, as a task that disappeared and that the API reports completed
+    #[serde(default)]
+    pub detected: bool,
+}
+
+/// Append completed tasks to the history log
+pub fn record_completions(tasks: &[Task], detected: bool) -> Result<()> {
+    if tasks.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = String::new();
+    for task in tasks {
+        let completion = Completion {
+            completed_at: Local::now(),
+            task_id: task.id.clone(),
+            project_id: task.project_id.clone(),
+            title: task.title.clone(),
+            detected,
+        };
+        lines.push_str(&serde_json::to_string(&completion)?);
+        lines.push('\n');
+    }
+
+    paths::append(&paths::history_file()?, &lines)
+}
+
+/// Read every recorded completion, skipping lines that can't be parsed
+pub fn read_completions() -> Result<Vec<Completion>> {
+    let history_path = paths::history_file()?;
+    if !history_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&history_path)
+        .map_err(|e| anyhow!("Failed to read history {}: {}", history_path.display(), e))?;
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Number of completions recorded on each day
+pub fn completions_per_day() -> Result<HashMap<NaiveDate, usize>> {
+    let mut counts = HashMap::new();
+    for completion in read_completions()? {
+        *counts
+            .entry(completion.completed_at.date_naive())
+            .or_insert(0) += 1;
+    }
+    Ok(counts)
+}
@@ -0,0 +1,51 @@
+use std::io::{self, IsTerminal, Write};
+use tokio::process::Command;
+
+use crate::config::HooksConfig;
+use crate::types::Task;
+
+/// The shell `hooks.on_complete_command` runs in
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// React to a task completed with `tick done`: ring the terminal bell if `hooks.bell` is on,
+/// then run `hooks.on_complete_command` with the task in TICK_* environment variables.
+/// Failures are only reported, the task stays completed.
+pub async fn task_completed(hooks: &HooksConfig, task: &Task, project_name: &str) {
+    // On stderr, and only in a terminal, so piped output isn't changed
+    if hooks.bell && io::stderr().is_terminal() {
+        eprint!("\x07");
+        let _ = io::stderr().flush();
+    }
+
+    let Some(command) = hooks.on_complete_command.as_deref() else {
+        return;
+    };
+    let status = shell(command)
+        .env("TICK_TASK_ID", &task.id)
+        .env("TICK_TASK_TITLE", &task.title)
+        .env("TICK_PROJECT_ID", &task.project_id)
+        .env("TICK_PROJECT_NAME", project_name)
+        .env("TICK_TASK_PRIORITY", task.priority.unwrap_or(0).to_string())
+        .env("TICK_TASK_DUE", task.due_date.as_deref().unwrap_or(""))
+        .env(
+            "TICK_TASK_TAGS",
+            task.tags.as_deref().unwrap_or_default().join(","),
+        )
+        .status()
+        .await;
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => println!("⚠️ on_complete_command exited with {}", status),
+        Err(e) => println!("⚠️ Failed to run on_complete_command: {}", e),
+    }
+}
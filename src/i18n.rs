@@ -0,0 +1,561 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::OnceLock;
+
+/// Languages tick's output is available in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+}
+
+impl Language {
+    /// Language from the first of LC_ALL, LC_MESSAGES and LANG that is set, e.g. `de_DE.UTF-8`
+    fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        match locale.split(['_', '.', '-']).next() {
+            Some("de") => Language::De,
+            _ => Language::En,
+        }
+    }
+}
+
+/// Regions whose calendars start the week on Sunday
+const SUNDAY_REGIONS: &[&str] = &[
+    "US", "CA", "MX", "BR", "JP", "KR", "TW", "HK", "PH", "IL", "IN", "ZA", "SA",
+];
+
+/// First day of the week for the region of LC_ALL, LC_TIME or LANG, e.g. `en_US.UTF-8`;
+/// Monday, the ISO convention, when there is none
+fn first_day_from_env() -> Weekday {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    match locale
+        .split(['.', '@'])
+        .next()
+        .and_then(|name| name.split_once('_'))
+    {
+        Some((_, region)) if SUNDAY_REGIONS.contains(&region) => Weekday::Sun,
+        _ => Weekday::Mon,
+    }
+}
+
+/// Every user-facing string of the translated output. Adding a field makes each locale
+/// fail to compile until it is translated.
+pub struct Catalog {
+    pub today: &'static str,
+    pub tomorrow: &'static str,
+    /// Abbreviated weekday names, Monday first
+    pub weekdays: [&'static str; 7],
+    /// Full weekday names, Monday first
+    pub weekdays_long: [&'static str; 7],
+    /// strftime format for a time of day
+    pub time_format: &'static str,
+    /// A date without the year, e.g. "May 01"
+    pub short_date: fn(NaiveDate) -> String,
+    /// A month and year, e.g. "May 2024"
+    pub month_year: fn(NaiveDate) -> String,
+    pub invalid_time: &'static str,
+
+    pub blocked_by: &'static str,
+    pub content: &'static str,
+    pub more_lines: fn(usize) -> String,
+    pub due: &'static str,
+    pub start: &'static str,
+    pub subtasks: &'static str,
+    pub project: &'static str,
+    pub tags: &'static str,
+    pub repeats: &'static str,
+    pub reminders: &'static str,
+    pub comments: fn(u32) -> String,
+    pub attachments: &'static str,
+    pub unnamed: &'static str,
+    pub inbox: &'static str,
+    pub unknown_project: &'static str,
+    pub task_count: fn(usize) -> String,
+    /// High, medium and low
+    pub priorities: [&'static str; 3],
+
+    pub syncing: &'static str,
+    pub no_matching_tasks: &'static str,
+    pub matching_tasks: fn(usize) -> String,
+    pub no_ready_tasks: &'static str,
+    pub no_blocked_tasks: &'static str,
+    pub ready_tasks: fn(usize) -> String,
+    pub blocked_tasks: fn(usize) -> String,
+
+    /// First and last day of the week
+    pub week_of: fn(NaiveDate, NaiveDate) -> String,
+
+    pub heatmap_title: fn(&str, bool) -> String,
+    pub less: &'static str,
+    pub more: &'static str,
+    /// Total for the month and the busiest day with its count, if any
+    pub month_total: fn(usize, Option<(String, usize)>) -> String,
+    pub completions_note: &'static str,
+
+    pub no_due_date: &'static str,
+    /// Number of days
+    pub due_over_next_days: fn(usize) -> String,
+    pub least_busy: &'static str,
+    /// Task title and day
+    pub snooze_question: fn(&str, &str) -> String,
+    /// Task title and new due date
+    pub snoozed: fn(&str, &str) -> String,
+    pub cancelled: &'static str,
+
+    /// Title with the date, e.g. "TickTick digest for Wednesday, May 1 2024"
+    pub digest_title: fn(NaiveDate) -> String,
+    pub overdue: &'static str,
+    pub due_today: &'static str,
+    pub upcoming: &'static str,
+    pub stats: &'static str,
+    pub nothing_overdue: &'static str,
+    pub nothing_due_today: &'static str,
+    pub nothing_due: &'static str,
+    pub open_tasks: &'static str,
+    pub blocked: &'static str,
+    pub completed_yesterday: &'static str,
+    pub completed_last_week: &'static str,
+    /// Number of sections that didn't fit in a Slack message
+    pub sections_left_out: fn(usize) -> String,
+    /// Name of the chat service
+    pub digest_posted: fn(&str) -> String,
+
+    pub fetching_today: &'static str,
+    pub cache_update_failed: &'static str,
+    pub all_caught_up: &'static str,
+    pub none_in_checked_projects: &'static str,
+    pub tasks_for_today: fn(usize) -> String,
+    /// Request limit, number of skipped projects and their names
+    pub budget_exhausted: fn(usize, usize, &str) -> String,
+    /// Request limit
+    pub project_list_skipped: fn(usize) -> String,
+    pub partial_results: &'static str,
+    pub fetch_failed: &'static str,
+}
+
+const EN_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const EN_MONTHS_LONG: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const DE_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+];
+const DE_MONTHS_LONG: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+
+const EN_WEEKDAYS_LONG: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const DE_WEEKDAYS_LONG: [&str; 7] = [
+    "Montag",
+    "Dienstag",
+    "Mittwoch",
+    "Donnerstag",
+    "Freitag",
+    "Samstag",
+    "Sonntag",
+];
+
+fn month_index(date: NaiveDate) -> usize {
+    date.month0() as usize
+}
+
+fn weekday_index(date: NaiveDate) -> usize {
+    date.weekday().num_days_from_monday() as usize
+}
+
+fn en_short_date(date: NaiveDate) -> String {
+    format!("{} {:02}", EN_MONTHS[month_index(date)], date.day())
+}
+
+fn de_short_date(date: NaiveDate) -> String {
+    format!("{:02}. {}", date.day(), DE_MONTHS[month_index(date)])
+}
+
+fn en_long_date(date: NaiveDate) -> String {
+    format!(
+        "{}, {} {} {}",
+        EN_WEEKDAYS_LONG[weekday_index(date)],
+        EN_MONTHS_LONG[month_index(date)],
+        date.day(),
+        date.year()
+    )
+}
+
+fn de_long_date(date: NaiveDate) -> String {
+    format!(
+        "{}, {}. {} {}",
+        DE_WEEKDAYS_LONG[weekday_index(date)],
+        date.day(),
+        DE_MONTHS_LONG[month_index(date)],
+        date.year()
+    )
+}
+
+const EN: Catalog = Catalog {
+    today: "Today",
+    tomorrow: "Tomorrow",
+    weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    weekdays_long: EN_WEEKDAYS_LONG,
+    time_format: "%I:%M %p",
+    short_date: en_short_date,
+    month_year: |date| format!("{} {}", EN_MONTHS_LONG[month_index(date)], date.year()),
+    invalid_time: "Invalid time",
+
+    blocked_by: "Blocked by",
+    content: "Content",
+    more_lines: |count| {
+        format!(
+            "… {} more line{} (use --expand or tick show to see everything)",
+            count,
+            if count == 1 { "" } else { "s" }
+        )
+    },
+    due: "Due",
+    start: "Start",
+    subtasks: "Subtasks",
+    project: "Project",
+    tags: "Tags",
+    repeats: "Repeats",
+    reminders: "Reminders",
+    comments: |count| {
+        format!(
+            "{} comment{} (view them in the TickTick app)",
+            count,
+            if count == 1 { "" } else { "s" }
+        )
+    },
+    attachments: "Attachments",
+    unnamed: "(unnamed)",
+    inbox: "Inbox",
+    unknown_project: "Unknown Project",
+    task_count: |count| format!("{} task{}", count, if count == 1 { "" } else { "s" }),
+    priorities: ["high", "medium", "low"],
+
+    syncing: "Syncing tasks...",
+    no_matching_tasks: "No matching tasks",
+    matching_tasks: |count| format!("{} matching task(s):", count),
+    no_ready_tasks: "No tasks are ready to work on",
+    no_blocked_tasks: "No blocked tasks",
+    ready_tasks: |count| format!("{} ready task(s):", count),
+    blocked_tasks: |count| format!("{} blocked task(s):", count),
+
+    week_of: |first, last| {
+        format!(
+            "Week of {} – {} {}",
+            en_short_date(first),
+            en_short_date(last),
+            last.year()
+        )
+    },
+
+    heatmap_title: |month, completed| {
+        format!(
+            "{} — tasks {}",
+            month,
+            if completed { "completed" } else { "due" }
+        )
+    },
+    less: "Less",
+    more: "More",
+    month_total: |total, busiest| match busiest {
+        Some((date, count)) => format!(
+            "Total: {} task(s), busiest day: {} ({})",
+            total, date, count
+        ),
+        None => "Total: 0 tasks".to_string(),
+    },
+    completions_note: "Completions are recorded when you finish tasks with tick, or detected \
+                       when a task completed elsewhere disappears between syncs.",
+
+    no_due_date: "no due date",
+    due_over_next_days: |days| format!("Tasks due over the next {} day(s):", days),
+    least_busy: "least busy",
+    snooze_question: |title, day| format!("Snooze \"{}\" to {}?", title, day),
+    snoozed: |title, due| format!("Snoozed \"{}\" until {}", title, due),
+    cancelled: "Cancelled",
+
+    digest_title: |date| format!("TickTick digest for {}", en_long_date(date)),
+    overdue: "Overdue",
+    due_today: "Due today",
+    upcoming: "Upcoming",
+    stats: "Stats",
+    nothing_overdue: "Nothing overdue",
+    nothing_due_today: "Nothing due today",
+    nothing_due: "Nothing due",
+    open_tasks: "Open tasks",
+    blocked: "Blocked",
+    completed_yesterday: "Completed yesterday",
+    completed_last_week: "Completed in the last 7 days",
+    sections_left_out: |count| format!("…and {} more part(s) that don't fit in one message", count),
+    digest_posted: |service| format!("Posted digest to {}", service),
+
+    fetching_today: "Fetching today's tasks...",
+    cache_update_failed: "Failed to update cache",
+    all_caught_up: "No tasks due today! You're all caught up!",
+    none_in_checked_projects: "No tasks due today in the projects that were checked.",
+    tasks_for_today: |count| format!("You have {} task(s) for today:", count),
+    budget_exhausted: |limit, count, names| {
+        format!(
+            "Request budget of {} exhausted, skipped {} project(s): {}",
+            limit, count, names
+        )
+    },
+    project_list_skipped: |limit| {
+        format!(
+            "Request budget of {} exhausted before the project list was fetched, so no project \
+             besides the Inbox could be checked; run once with a larger budget to cache the list",
+            limit
+        )
+    },
+    partial_results: "Results are partial because the request budget ran out",
+    fetch_failed: "Error fetching tasks",
+};
+
+const DE: Catalog = Catalog {
+    today: "Heute",
+    tomorrow: "Morgen",
+    weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    weekdays_long: DE_WEEKDAYS_LONG,
+    time_format: "%H:%M",
+    short_date: de_short_date,
+    month_year: |date| format!("{} {}", DE_MONTHS_LONG[month_index(date)], date.year()),
+    invalid_time: "Ungültige Zeit",
+
+    blocked_by: "Blockiert durch",
+    content: "Inhalt",
+    more_lines: |count| {
+        format!(
+            "… {} weitere Zeile{} (mit --expand oder tick show alles anzeigen)",
+            count,
+            if count == 1 { "" } else { "n" }
+        )
+    },
+    due: "Fällig",
+    start: "Beginn",
+    subtasks: "Unteraufgaben",
+    project: "Projekt",
+    tags: "Tags",
+    repeats: "Wiederholung",
+    reminders: "Erinnerungen",
+    comments: |count| {
+        format!(
+            "{} Kommentar{} (in der TickTick-App ansehen)",
+            count,
+            if count == 1 { "" } else { "e" }
+        )
+    },
+    attachments: "Anhänge",
+    unnamed: "(ohne Namen)",
+    inbox: "Eingang",
+    unknown_project: "Unbekanntes Projekt",
+    task_count: |count| format!("{} Aufgabe{}", count, if count == 1 { "" } else { "n" }),
+    priorities: ["hoch", "mittel", "niedrig"],
+
+    syncing: "Aufgaben werden synchronisiert...",
+    no_matching_tasks: "Keine passenden Aufgaben",
+    matching_tasks: |count| format!("{} passende Aufgabe(n):", count),
+    no_ready_tasks: "Keine Aufgabe ist bereit zur Bearbeitung",
+    no_blocked_tasks: "Keine blockierten Aufgaben",
+    ready_tasks: |count| format!("{} bereite Aufgabe(n):", count),
+    blocked_tasks: |count| format!("{} blockierte Aufgabe(n):", count),
+
+    week_of: |first, last| {
+        format!(
+            "Woche vom {} – {} {}",
+            de_short_date(first),
+            de_short_date(last),
+            last.year()
+        )
+    },
+
+    heatmap_title: |month, completed| {
+        format!(
+            "{} — {} Aufgaben",
+            month,
+            if completed { "erledigte" } else { "fällige" }
+        )
+    },
+    less: "Weniger",
+    more: "Mehr",
+    month_total: |total, busiest| match busiest {
+        Some((date, count)) => format!(
+            "Gesamt: {} Aufgabe(n), meiste am {} ({})",
+            total, date, count
+        ),
+        None => "Gesamt: 0 Aufgaben".to_string(),
+    },
+    completions_note: "Erledigungen werden erfasst, wenn du Aufgaben mit tick abschließt, oder \
+                       erkannt, wenn eine anderswo erledigte Aufgabe zwischen zwei \
+                       Synchronisierungen verschwindet.",
+
+    no_due_date: "kein Fälligkeitsdatum",
+    due_over_next_days: |days| format!("Fällige Aufgaben in den nächsten {} Tag(en):", days),
+    least_busy: "am wenigsten los",
+    snooze_question: |title, day| format!("\"{}\" auf {} verschieben?", title, day),
+    snoozed: |title, due| format!("\"{}\" verschoben auf {}", title, due),
+    cancelled: "Abgebrochen",
+
+    digest_title: |date| format!("TickTick-Übersicht für {}", de_long_date(date)),
+    overdue: "Überfällig",
+    due_today: "Heute fällig",
+    upcoming: "Demnächst",
+    stats: "Statistik",
+    nothing_overdue: "Nichts überfällig",
+    nothing_due_today: "Heute nichts fällig",
+    nothing_due: "Nichts fällig",
+    open_tasks: "Offene Aufgaben",
+    blocked: "Blockiert",
+    completed_yesterday: "Gestern erledigt",
+    completed_last_week: "In den letzten 7 Tagen erledigt",
+    sections_left_out: |count| {
+        format!(
+            "…und {} weitere(r) Teil(e), die nicht in eine Nachricht passen",
+            count
+        )
+    },
+    digest_posted: |service| format!("Übersicht an {} gesendet", service),
+
+    fetching_today: "Heutige Aufgaben werden geladen...",
+    cache_update_failed: "Cache konnte nicht aktualisiert werden",
+    all_caught_up: "Heute ist nichts fällig! Alles erledigt!",
+    none_in_checked_projects: "In den geprüften Projekten ist heute nichts fällig.",
+    tasks_for_today: |count| format!("Du hast heute {} Aufgabe(n):", count),
+    budget_exhausted: |limit, count, names| {
+        format!(
+            "Anfragelimit von {} erreicht, {} Projekt(e) übersprungen: {}",
+            limit, count, names
+        )
+    },
+    project_list_skipped: |limit| {
+        format!(
+            "Anfragelimit von {} erreicht, bevor die Projektliste geladen wurde, daher konnte \
+             außer dem Eingang kein Projekt geprüft werden; einmal mit höherem Limit ausführen, \
+             um die Liste zu speichern",
+            limit
+        )
+    },
+    partial_results: "Die Ergebnisse sind unvollständig, weil das Anfragelimit erreicht wurde",
+    fetch_failed: "Fehler beim Laden der Aufgaben",
+};
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Choose the output language for the rest of the run, from `display.language`
+pub fn set_language(language: Language) {
+    let _ = LANGUAGE.set(language);
+}
+
+static FIRST_DAY: OnceLock<Weekday> = OnceLock::new();
+
+/// Choose the first day of the week for the rest of the run, from `display.first_day_of_week`
+pub fn set_first_day(first_day: Weekday) {
+    let _ = FIRST_DAY.set(first_day);
+}
+
+/// The configured first day of the week, or the one usual in the environment's region
+pub fn first_day() -> Weekday {
+    *FIRST_DAY.get_or_init(first_day_from_env)
+}
+
+/// Abbreviated weekday names in the configured language, from the first day of the week
+pub fn weekdays_in_order() -> Vec<&'static str> {
+    let first = first_day().num_days_from_monday() as usize;
+    (0..7)
+        .map(|day| text().weekdays[(first + day) % 7])
+        .collect()
+}
+
+/// A date with its abbreviated weekday, e.g. "Wed May 01"
+pub fn short_day(date: NaiveDate) -> String {
+    format!(
+        "{} {}",
+        text().weekdays[weekday_index(date)],
+        (text().short_date)(date)
+    )
+}
+
+/// A date with its full weekday, e.g. "Wednesday, May 01"
+pub fn day_heading(date: NaiveDate) -> String {
+    format!(
+        "{}, {}",
+        text().weekdays_long[weekday_index(date)],
+        (text().short_date)(date)
+    )
+}
+
+/// Strings in the configured language, or the one the environment's locale asks for
+pub fn text() -> &'static Catalog {
+    match LANGUAGE.get_or_init(Language::from_env) {
+        Language::En => &EN,
+        Language::De => &DE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_follow_each_language() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(en_short_date(date), "May 01");
+        assert_eq!(de_short_date(date), "01. Mai");
+        assert_eq!(
+            (EN.digest_title)(date),
+            "TickTick digest for Wednesday, May 1 2024"
+        );
+        assert_eq!(
+            (DE.digest_title)(date),
+            "TickTick-Übersicht für Mittwoch, 1. Mai 2024"
+        );
+        assert_eq!(
+            (DE.week_of)(date, date + chrono::Days::new(6)),
+            "Woche vom 01. Mai – 07. Mai 2024"
+        );
+    }
+}
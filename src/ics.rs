@@ -0,0 +1,298 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::paths;
+
+/// Start or end of an event, either a whole day or a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventTime {
+    Date(NaiveDate),
+    DateTime(DateTime<Local>),
+}
+
+impl EventTime {
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            EventTime::Date(date) => *date,
+            EventTime::DateTime(datetime) => datetime.date_naive(),
+        }
+    }
+}
+
+/// The parts of a VEVENT that tick turns into a task
+#[derive(Debug, Clone)]
+pub struct IcsEvent {
+    pub uid: Option<String>,
+    pub recurrence_id: Option<String>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub start: EventTime,
+    pub end: Option<EventTime>,
+}
+
+impl IcsEvent {
+    /// Key used to recognise an event on later imports; moved occurrences of a recurring
+    /// event share the UID but carry their own RECURRENCE-ID
+    pub fn import_key(&self) -> String {
+        match (&self.uid, &self.recurrence_id) {
+            (Some(uid), Some(recurrence_id)) => format!("{}#{}", uid, recurrence_id),
+            (Some(uid), None) => uid.clone(),
+            (None, _) => format!("{}#{:?}", self.summary, self.start),
+        }
+    }
+}
+
+/// Join folded lines: a line starting with a space or tab continues the previous one
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in input.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split `NAME;PARAM=value:VALUE` into name, params and value, ignoring colons in quoted params
+fn split_property(line: &str) -> Option<(String, HashMap<String, String>, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(index),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.to_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text
+}
+
+/// Parse DTSTART/DTEND values. Times with a TZID are treated as local time, since tick has
+/// no time zone database; UTC times (ending in `Z`) are converted properly.
+fn parse_event_time(params: &HashMap<String, String>, value: &str) -> Result<EventTime> {
+    let invalid = || anyhow!("Invalid date '{}' in calendar", value);
+
+    if params.get("VALUE").map(String::as_str) == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(EventTime::Date)
+            .map_err(|_| invalid());
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(EventTime::DateTime(
+            Utc.from_utc_datetime(&naive).with_timezone(&Local),
+        ));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(EventTime::DateTime)
+        .ok_or_else(invalid)
+}
+
+/// Property name mapped to its parameters and raw value
+type Properties = HashMap<String, (HashMap<String, String>, String)>;
+
+/// Every VEVENT in an iCalendar file, and why the ones that couldn't be read were skipped.
+/// Events without a DTSTART, or with a DTSTART or DTEND that can't be parsed, are skipped.
+pub fn parse_events(input: &str) -> (Vec<IcsEvent>, Vec<String>) {
+    let mut events = Vec::new();
+    let mut skipped = Vec::new();
+    let mut current: Option<Properties> = None;
+    // Depth of components nested inside the current event, such as VALARM
+    let mut nested = 0;
+
+    for line in unfold(input) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+
+        match (name.as_str(), value.to_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(HashMap::new()),
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if nested > 0 => nested -= 1,
+            _ if nested > 0 => {}
+            ("END", "VEVENT") => {
+                let Some(properties) = current.take() else {
+                    continue;
+                };
+                let text = |name: &str| {
+                    properties
+                        .get(name)
+                        .map(|(_, value)| unescape_text(value))
+                        .filter(|value| !value.trim().is_empty())
+                };
+                let summary = text("SUMMARY").unwrap_or_else(|| "(untitled event)".to_string());
+
+                let Some((start_params, start)) = properties.get("DTSTART") else {
+                    skipped.push(format!("\"{}\": no DTSTART", summary));
+                    continue;
+                };
+                let start = match parse_event_time(start_params, start) {
+                    Ok(start) => start,
+                    Err(e) => {
+                        skipped.push(format!("\"{}\": DTSTART: {}", summary, e));
+                        continue;
+                    }
+                };
+                let end = match properties.get("DTEND") {
+                    Some((params, value)) => match parse_event_time(params, value) {
+                        Ok(end) => Some(end),
+                        Err(e) => {
+                            skipped.push(format!("\"{}\": DTEND: {}", summary, e));
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+
+                events.push(IcsEvent {
+                    uid: text("UID"),
+                    recurrence_id: properties
+                        .get("RECURRENCE-ID")
+                        .map(|(_, value)| value.clone()),
+                    summary,
+                    description: text("DESCRIPTION"),
+                    location: text("LOCATION"),
+                    start,
+                    end,
+                });
+            }
+            _ => {
+                if let Some(properties) = current.as_mut() {
+                    properties
+                        .entry(name)
+                        .or_insert_with(|| (params, value.to_string()));
+                }
+            }
+        }
+    }
+
+    (events, skipped)
+}
+
+/// Import keys of events already turned into tasks, mapped to the task id
+pub fn load_imported() -> Result<HashMap<String, String>> {
+    let path = paths::ics_imports_file()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn save_imported(imported: &HashMap<String, String>) -> Result<()> {
+    let path = paths::ics_imports_file()?;
+    paths::write_atomic(&path, &serde_json::to_string_pretty(imported)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(events: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n",
+            events.replace('\n', "\r\n")
+        )
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> EventTime {
+        let naive = NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap();
+        EventTime::DateTime(Local.from_local_datetime(&naive).earliest().unwrap())
+    }
+
+    #[test]
+    fn all_day_events_are_dates() {
+        let (events, skipped) = parse_events(&calendar(
+            "BEGIN:VEVENT\nUID:1\nSUMMARY:Holiday\nDTSTART;VALUE=DATE:20240501\n\
+             DTEND;VALUE=DATE:20240502\nEND:VEVENT\n",
+        ));
+        assert!(skipped.is_empty());
+        let date = |day| EventTime::Date(NaiveDate::from_ymd_opt(2024, 5, day).unwrap());
+        assert_eq!(events[0].start, date(1));
+        assert_eq!(events[0].end, Some(date(2)));
+    }
+
+    #[test]
+    fn times_with_a_tzid_are_local_and_utc_times_converted() {
+        let (events, _) = parse_events(&calendar(
+            "BEGIN:VEVENT\nSUMMARY:Standup\nDTSTART;TZID=\"Europe/Berlin\":20240501T090000\n\
+             DTEND:20240501T093000Z\nEND:VEVENT\n",
+        ));
+        assert_eq!(events[0].start, local(2024, 5, 1, 9, 0));
+        let utc = Utc.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+        assert_eq!(
+            events[0].end,
+            Some(EventTime::DateTime(utc.with_timezone(&Local)))
+        );
+    }
+
+    #[test]
+    fn folded_lines_are_joined() {
+        let (events, _) = parse_events(&calendar(
+            "BEGIN:VEVENT\nSUMMARY:Quarterly plan\n ning review\nDESCRIPTION:Bring\\n\n\tnotes\n\
+             DTSTART:20240501T100000\nEND:VEVENT\n",
+        ));
+        assert_eq!(events[0].summary, "Quarterly planning review");
+        assert_eq!(events[0].description.as_deref(), Some("Bring\nnotes"));
+    }
+
+    #[test]
+    fn events_with_bad_dates_are_skipped_and_reported() {
+        let (events, skipped) = parse_events(&calendar(
+            "BEGIN:VEVENT\nSUMMARY:Bad start\nDTSTART:2024-05-01\nEND:VEVENT\n\
+             BEGIN:VEVENT\nSUMMARY:Bad end\nDTSTART:20240501T100000\nDTEND:soon\nEND:VEVENT\n\
+             BEGIN:VEVENT\nSUMMARY:No start\nEND:VEVENT\n\
+             BEGIN:VEVENT\nSUMMARY:Fine\nDTSTART:20240501T100000\nEND:VEVENT\n",
+        ));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Fine");
+        assert_eq!(
+            skipped,
+            [
+                "\"Bad start\": DTSTART: Invalid date '2024-05-01' in calendar",
+                "\"Bad end\": DTEND: Invalid date 'soon' in calendar",
+                "\"No start\": no DTSTART",
+            ]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::types::Priority;
+
+/// One task in the JSON array read by `tick add --json-stdin`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    pub title: String,
+    /// Project name; `--project` or the inbox when missing
+    pub project: Option<String>,
+    /// Same formats as `--due`, e.g. "2024-05-01" or "May 1 5pm"
+    pub due: Option<String>,
+    /// "high", "medium", "low" or "none"
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Titles of checklist items, in order
+    #[serde(default)]
+    pub checklist: Vec<String>,
+    /// Task notes
+    pub content: Option<String>,
+}
+
+/// Parse a JSON array of tasks, naming the position of the first invalid one
+pub fn parse(json: &str) -> Result<Vec<TaskSpec>> {
+    let values: Vec<Value> = serde_json::from_str(json)
+        .map_err(|e| anyhow!("Expected a JSON array of task objects: {}", e))?;
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let spec: TaskSpec = serde_json::from_value(value)
+                .map_err(|e| anyhow!("Task {} in the input: {}", index + 1, e))?;
+            if spec.title.trim().is_empty() {
+                return Err(anyhow!(
+                    "Task {} in the input has an empty title",
+                    index + 1
+                ));
+            }
+            Ok(spec)
+        })
+        .collect()
+}
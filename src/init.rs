@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use std::io::{self, Write};
+
+use crate::auth::perform_oauth_flow;
+use crate::client::TickTickClient;
+use crate::config::{redirect_uri_for_port, Config, TickTickConfig, TokenStorage};
+
+/// Print a prompt and read a trimmed line, falling back to `default` on empty input
+fn prompt(label: &str, default: Option<&str>) -> Result<String> {
+    match default {
+        Some(default) => print!("{} [{}]: ", label, default),
+        None => print!("{}: ", label),
+    }
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Err(anyhow!("Setup cancelled"));
+    }
+
+    let input = input.trim();
+    Ok(match default {
+        Some(default) if input.is_empty() => default.to_string(),
+        _ => input.to_string(),
+    })
+}
+
+/// Keep prompting until the input passes validation
+fn prompt_valid<T>(
+    label: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<T, String>,
+) -> Result<T> {
+    loop {
+        let input = prompt(label, default)?;
+        match validate(&input) {
+            Ok(value) => return Ok(value),
+            Err(message) => println!("  ❌ {}", message),
+        }
+    }
+}
+
+fn validate_credential(input: &str) -> Result<String, String> {
+    if input.is_empty() {
+        Err("This value is required".to_string())
+    } else if input.chars().any(char::is_whitespace) {
+        Err("This value must not contain spaces".to_string())
+    } else {
+        Ok(input.to_string())
+    }
+}
+
+fn validate_port(input: &str) -> Result<u16, String> {
+    match input.parse::<u16>() {
+        Ok(port) if port >= 1024 => Ok(port),
+        _ => Err("Enter a port number between 1024 and 65535".to_string()),
+    }
+}
+
+fn validate_storage(input: &str) -> Result<TokenStorage, String> {
+    match input.to_lowercase().as_str() {
+        "file" => Ok(TokenStorage::File),
+        "keyring" => Ok(TokenStorage::Keyring),
+        "encrypted" => Ok(TokenStorage::Encrypted),
+        _ => Err("Enter 'file', 'keyring' or 'encrypted'".to_string()),
+    }
+}
+
+/// Guided first-run setup: credentials, callback port, token storage, OAuth and a test fetch
+pub async fn run_init() -> Result<(TickTickClient, Config)> {
+    println!("🎯 TickTick Setup");
+    println!("=================");
+    println!();
+
+    if Config::exists() {
+        let answer = prompt(
+            "A configuration file already exists. Overwrite it? (y/N)",
+            None,
+        )?;
+        if !matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+            return Err(anyhow!(
+                "Setup cancelled, existing configuration left unchanged"
+            ));
+        }
+        println!();
+    }
+
+    println!("Step 1: Choose a port for the local authorization callback server");
+    let port = prompt_valid("Callback port", Some("8080"), validate_port)?;
+    let redirect_uri = redirect_uri_for_port(port);
+    println!();
+
+    println!("Step 2: Register an application with TickTick");
+    println!("  1. Visit https://developer.ticktick.com/ and sign in");
+    println!("  2. Open \"Manage Apps\" and create a new app");
+    println!(
+        "  3. Set the OAuth redirect URL to exactly: {}",
+        redirect_uri
+    );
+    println!("  4. Copy the app's client_id and client_secret");
+    println!();
+
+    let client_id = prompt_valid("Enter your client_id", None, validate_credential)?;
+    let client_secret = prompt_valid("Enter your client_secret", None, validate_credential)?;
+    println!();
+
+    println!("Step 3: Choose where to store your access token");
+    println!("  file      - in the configuration file");
+    println!("  keyring   - in your operating system's keyring");
+    println!("  encrypted - in the configuration file, encrypted with a passphrase");
+    let token_storage = prompt_valid("Token storage", Some("file"), validate_storage)?;
+    println!();
+
+    let mut config = Config {
+        ticktick: TickTickConfig {
+            client_id,
+            client_secret,
+            redirect_uri,
+            token_storage,
+            access_token: None,
+            refresh_token: None,
+            token_expires_at: None,
+        },
+        filters: Default::default(),
+        notify: Default::default(),
+        display: Default::default(),
+        theme: Default::default(),
+        todos: Default::default(),
+        github: Default::default(),
+        sync: Default::default(),
+        http: Default::default(),
+        hooks: Default::default(),
+        mail: None,
+    };
+    config.save()?;
+    println!(
+        "✅ Saved configuration to {}",
+        Config::config_path()?.display()
+    );
+    println!();
+
+    println!("Step 4: Authorize tick with your TickTick account");
+    let mut client = TickTickClient::new(
+        config.ticktick.client_id.clone(),
+        config.ticktick.client_secret.clone(),
+        config.ticktick.redirect_uri.clone(),
+    );
+    perform_oauth_flow(&mut client, &mut config).await?;
+    println!();
+
+    println!("Step 5: Testing the connection...");
+    let projects = client.get_projects().await?;
+    println!("✅ Connected! Found {} project(s).", projects.len());
+    println!();
+    println!("🎉 Setup complete! Run `tick` to see today's tasks.");
+
+    Ok((client, config))
+}
@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::paths;
+
+/// Positional references have at most this many digits; longer numbers are id prefixes
+const MAX_POSITION_DIGITS: usize = 3;
+
+/// Prefix that makes a reference an id or id prefix even when it looks like a position
+const ID_PREFIX: &str = "id:";
+
+/// Positions older than this are refused, as the tasks have likely changed since
+const MAX_AGE_HOURS: i64 = 8;
+
+/// The tasks the last `today`, `list`, `blocked` or `ready` showed, numbered from 1
+#[derive(Debug, Deserialize, Serialize)]
+struct Listing {
+    /// The command that showed them, e.g. "list"
+    command: String,
+    shown_at: DateTime<Local>,
+    task_ids: Vec<String>,
+}
+
+/// Remember the tasks a command just showed, in the order they were numbered
+pub fn save(command: &str, task_ids: Vec<String>) -> Result<()> {
+    let listing = Listing {
+        command: command.to_string(),
+        shown_at: Local::now(),
+        task_ids,
+    };
+    paths::write_atomic(
+        &paths::last_listing_file()?,
+        &serde_json::to_string_pretty(&listing)?,
+    )
+}
+
+fn load() -> Result<Option<Listing>> {
+    let path = paths::last_listing_file()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let listing = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+    Ok(Some(listing))
+}
+
+fn position(text: &str) -> Option<usize> {
+    if text.is_empty()
+        || text.len() > MAX_POSITION_DIGITS
+        || !text.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// The first and last position of a reference like `3` or `2-5`
+fn positions(reference: &str) -> Option<(usize, usize)> {
+    match reference.split_once('-') {
+        Some((first, last)) => Some((position(first)?, position(last)?)),
+        None => position(reference).map(|single| (single, single)),
+    }
+}
+
+/// Refuse a listing shown too long before `now` to trust its numbers
+fn check_age(listing: &Listing, now: DateTime<Local>) -> Result<()> {
+    let age = now - listing.shown_at;
+    if age <= Duration::hours(MAX_AGE_HOURS) {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Task numbers are from `tick {}` on {}, more than {} hours ago, and may point at other \
+         tasks by now. List again, or refer to the task by id.",
+        listing.command,
+        listing.shown_at.format("%b %d %H:%M"),
+        MAX_AGE_HOURS
+    ))
+}
+
+/// Task ids a reference stands for: the tasks at a position such as `3` or a range such as
+/// `2-5` in the last listing, or otherwise the reference itself as an id or id prefix.
+/// `id:` in front, e.g. `id:123`, makes it an id prefix even if it looks like a position.
+pub fn expand(reference: &str) -> Result<Vec<String>> {
+    expand_with(reference, load)
+}
+
+/// `expand` against the listing `load` returns, which is only read for positions
+fn expand_with(
+    reference: &str,
+    load: impl FnOnce() -> Result<Option<Listing>>,
+) -> Result<Vec<String>> {
+    if let Some(id) = reference.strip_prefix(ID_PREFIX) {
+        return Ok(vec![id.to_string()]);
+    }
+    let Some((first, last)) = positions(reference) else {
+        return Ok(vec![reference.to_string()]);
+    };
+    if first == 0 || last < first {
+        return Err(anyhow!(
+            "'{}' isn't a valid position, they count from 1 and ranges go upwards",
+            reference
+        ));
+    }
+
+    let listing = load()?.ok_or_else(|| {
+        anyhow!("No tasks have been listed yet, run `tick today` or `tick list` to number them")
+    })?;
+    check_age(&listing, Local::now())?;
+    if last > listing.task_ids.len() {
+        return Err(anyhow!(
+            "`tick {}` ({}) showed {} task(s), so there is no task {}",
+            listing.command,
+            listing.shown_at.format("%b %d %H:%M"),
+            listing.task_ids.len(),
+            last
+        ));
+    }
+    Ok(listing.task_ids[first - 1..last].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_numbers_are_positions() {
+        assert_eq!(positions("3"), Some((3, 3)));
+        assert_eq!(positions("2-5"), Some((2, 5)));
+        assert_eq!(positions("1234"), None);
+        assert_eq!(positions("65a1"), None);
+        assert_eq!(positions("2-"), None);
+    }
+
+    #[test]
+    fn id_prefix_skips_the_listing() {
+        assert_eq!(expand("id:123").unwrap(), ["123"]);
+        assert_eq!(expand("65a1f2c3").unwrap(), ["65a1f2c3"]);
+    }
+
+    #[test]
+    fn positions_pick_the_listed_tasks() {
+        let listed = || {
+            Ok(Some(Listing {
+                command: "list".to_string(),
+                shown_at: Local::now(),
+                task_ids: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            }))
+        };
+        assert_eq!(expand_with("3", listed).unwrap(), ["c"]);
+        assert_eq!(expand_with("1-2", listed).unwrap(), ["a", "b"]);
+        assert_eq!(expand_with("id:3", listed).unwrap(), ["3"]);
+        assert!(expand_with("4", listed).is_err());
+        assert!(expand_with("3", || Ok(None)).is_err());
+    }
+
+    #[test]
+    fn old_listings_are_refused() {
+        let now = Local::now();
+        let listing = |hours| Listing {
+            command: "list".to_string(),
+            shown_at: now - Duration::hours(hours),
+            task_ids: vec!["a".to_string()],
+        };
+        assert!(check_age(&listing(1), now).is_ok());
+        assert!(check_age(&listing(MAX_AGE_HOURS + 1), now).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{anyhow, Result};
+use mailparse::{MailAddr, MailHeaderMap, ParsedMail};
+use native_tls::{TlsConnector, TlsStream};
+use std::env;
+use std::net::TcpStream;
+
+use crate::config::{Config, MailConfig};
+
+/// Environment variable that supplies the mailbox password instead of the config file
+pub const PASSWORD_ENV: &str = "TICK_MAIL_PASSWORD";
+
+/// Name of the mailbox password in the system keyring
+pub const PASSWORD_SECRET: &str = "mail_password";
+
+/// Longest message body copied into a task, in characters
+const MAX_BODY_CHARS: usize = 2000;
+
+/// An unseen message, ready to become a task
+pub struct Message {
+    pub uid: u32,
+    pub subject: String,
+    /// The From header as sent, e.g. `Alice <alice@example.com>`
+    pub from: String,
+    /// The sender's address, e.g. `alice@example.com`
+    pub sender: String,
+    /// Start of the plain text body, empty for messages without one
+    pub body: String,
+}
+
+impl Message {
+    /// Tag naming the sender, e.g. `from-alice-example-com`
+    pub fn sender_tag(&self) -> String {
+        let address: String = self
+            .sender
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        format!("from-{}", address.trim_matches('-'))
+    }
+
+    /// Task content: who sent it, then the body
+    pub fn content(&self) -> String {
+        if self.body.is_empty() {
+            format!("From: {}", self.from)
+        } else {
+            format!("From: {}\n\n{}", self.from, self.body)
+        }
+    }
+}
+
+/// Password from TICK_MAIL_PASSWORD, `mail.password` in the keyring or the (possibly
+/// encrypted) config file, or a prompt
+pub fn password(config: &Config, mail: &MailConfig) -> Result<String> {
+    if let Some(password) = env::var(PASSWORD_ENV).ok().filter(|p| !p.is_empty()) {
+        return Ok(password);
+    }
+    if let Some(password) = config.secret(PASSWORD_SECRET, mail.password.as_deref())? {
+        return Ok(password);
+    }
+    rpassword::prompt_password(format!("🔐 Password for {}: ", mail.username)).map_err(|e| {
+        anyhow!(
+            "Failed to read password ({}), set {} to provide it non-interactively",
+            e,
+            PASSWORD_ENV
+        )
+    })
+}
+
+/// A logged-in IMAP session with the configured folder selected
+pub struct Mailbox {
+    session: imap::Session<TlsStream<TcpStream>>,
+}
+
+impl Mailbox {
+    pub fn open(config: &MailConfig, password: &str) -> Result<Self> {
+        let tls = TlsConnector::new()?;
+        let client = imap::connect((config.host.as_str(), config.port), &config.host, &tls)
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to connect to {}:{}: {}",
+                    config.host,
+                    config.port,
+                    e
+                )
+            })?;
+        let mut session = client
+            .login(&config.username, password)
+            .map_err(|(e, _)| anyhow!("IMAP login as {} failed: {}", config.username, e))?;
+        session
+            .select(&config.folder)
+            .map_err(|e| anyhow!("Failed to open folder {}: {}", config.folder, e))?;
+        Ok(Self { session })
+    }
+
+    /// Unseen messages, oldest first. They are read with BODY.PEEK so they stay unseen
+    /// until `mark_seen`.
+    pub fn unseen(&mut self) -> Result<Vec<Message>> {
+        let uids = self.session.uid_search("UNSEEN")?;
+        if uids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let uid_set: Vec<String> = uids.iter().map(u32::to_string).collect();
+        let fetches = self
+            .session
+            .uid_fetch(uid_set.join(","), "(UID BODY.PEEK[])")?;
+        let mut messages = Vec::new();
+        for fetch in fetches.iter() {
+            let (Some(uid), Some(raw)) = (fetch.uid, fetch.body()) else {
+                continue;
+            };
+            messages.push(parse_message(uid, raw)?);
+        }
+        messages.sort_by_key(|message| message.uid);
+        Ok(messages)
+    }
+
+    pub fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.session
+            .uid_store(uid.to_string(), "+FLAGS (\\Seen)")
+            .map_err(|e| anyhow!("Failed to mark message {} as seen: {}", uid, e))?;
+        Ok(())
+    }
+
+    pub fn logout(mut self) -> Result<()> {
+        self.session.logout()?;
+        Ok(())
+    }
+}
+
+fn parse_message(uid: u32, raw: &[u8]) -> Result<Message> {
+    let mail = mailparse::parse_mail(raw)
+        .map_err(|e| anyhow!("Failed to parse message {}: {}", uid, e))?;
+
+    let subject = mail
+        .headers
+        .get_first_value("Subject")
+        .map(|subject| subject.trim().to_string())
+        .filter(|subject| !subject.is_empty())
+        .unwrap_or_else(|| "(no subject)".to_string());
+    let from = mail.headers.get_first_value("From").unwrap_or_default();
+    let addresses = mailparse::addrparse(&from).ok();
+    let sender = match addresses.as_ref().and_then(|addresses| addresses.first()) {
+        Some(MailAddr::Single(info)) => info.addr.clone(),
+        _ => from.trim().to_string(),
+    };
+
+    Ok(Message {
+        uid,
+        subject,
+        from: from.trim().to_string(),
+        sender,
+        body: plain_text(&mail).map(snippet).unwrap_or_default(),
+    })
+}
+
+/// First text/plain part, searching nested multipart messages depth first
+fn plain_text(mail: &ParsedMail) -> Option<String> {
+    if mail.subparts.is_empty() {
+        return match mail.ctype.mimetype.as_str() {
+            "text/plain" => mail.get_body().ok(),
+            _ => None,
+        };
+    }
+    mail.subparts.iter().find_map(plain_text)
+}
+
+/// The body without trailing whitespace, cut off after MAX_BODY_CHARS
+fn snippet(body: String) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(MAX_BODY_CHARS) {
+        Some((end, _)) => format!("{}…", body[..end].trim_end()),
+        None => body.to_string(),
+    }
+}
@@ -1,118 +1,354 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::Parser;
 use std::env;
+use std::time::Duration;
 
+mod audit;
 mod auth;
+mod cache;
+mod cli;
 mod client;
+mod clipboard;
+mod commands;
 mod config;
+mod crypt;
+mod dates;
+mod dedup;
+mod deps;
+mod digest;
 mod display;
+mod explain;
+mod filter;
+mod github;
+mod grep;
+mod help;
+mod hooks;
+mod history;
+mod i18n;
+mod ics;
+mod ingest;
+mod init;
+mod listing;
+mod mail;
+mod metrics;
+mod mqtt;
+mod notify;
+mod paths;
+mod quickadd;
+mod reorder;
+mod settings;
+mod todos;
+mod track;
 mod types;
+mod urgency;
 
-use auth::{interactive_auth, perform_oauth_flow};
-use client::TickTickClient;
+use auth::perform_oauth_flow;
+use cli::{Cli, Command, GithubAction, ImportSource, MailAction, TodosAction, TrackAction};
+use client::{ParseReport, TickTickClient};
 use config::Config;
-use display::print_tasks_grouped;
+use metrics::METRICS;
+use types::OAuthToken;
+use init::run_init;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Try to load from environment variables first
-    let client = if let (Ok(client_id), Ok(client_secret), Ok(redirect_uri), Ok(access_token)) = (
-        env::var("TICKTICK_CLIENT_ID"),
-        env::var("TICKTICK_CLIENT_SECRET"),
-        env::var("TICKTICK_REDIRECT_URI"),
-        env::var("TICKTICK_ACCESS_TOKEN"),
-    ) {
-        let mut client = TickTickClient::new(client_id, client_secret, redirect_uri);
-        client.access_token = Some(access_token);
-        println!("✅ Using credentials from environment variables");
-        client
+    let mut cli = Cli::parse();
+
+    // Commands that don't talk to the API skip configuration and authentication entirely
+    match cli.command.take().unwrap_or(Command::Today) {
+        Command::Init => run_init().await.map(|_| ()),
+        Command::Log { limit } => commands::run_log(limit),
+        Command::Grep(args) => commands::run_grep(args),
+        Command::Config { action } => commands::run_config(action),
+        Command::Paths => commands::run_paths(),
+        Command::Man { dir } => commands::run_man(&dir),
+        Command::Track {
+            action: TrackAction::Status,
+        } => commands::run_track_status(),
+        Command::Track {
+            action: TrackAction::Stop { append: false },
+        } => commands::run_track_stop(None).await,
+        Command::Stats { days } => commands::run_stats(days),
+        command => run_api_command(command, &cli).await,
+    }
+}
+
+async fn run_api_command(command: Command, cli: &Cli) -> Result<()> {
+    let (mut client, mut config) = authenticate().await?;
+    let display_config = config.as_ref().map(|config| &config.display);
+    if let Some(display_config) = display_config {
+        display::set_time_style(display_config.time_style);
+        if let Some(language) = display_config.language {
+            i18n::set_language(language);
+        }
+        if let Some(first_day) = display_config.first_day_of_week {
+            i18n::set_first_day(first_day);
+        }
+    }
+    display::set_options(display::Options {
+        project_order: cli
+            .project_order
+            .or(display_config.map(|display| display.project_order))
+            .unwrap_or_default(),
+        task_order: cli
+            .task_order
+            .or(display_config.map(|display| display.task_order))
+            .unwrap_or_default(),
+        hide_done_items: cli.hide_done_items,
+        content_lines: if cli.expand {
+            None
+        } else {
+            Some(
+                display_config
+                    .and_then(|display| display.content_lines)
+                    .unwrap_or(display::DEFAULT_CONTENT_LINES),
+            )
+        },
+        due_soon: chrono::Duration::minutes(
+            config
+                .as_ref()
+                .and_then(|config| config.theme.soon_minutes)
+                .unwrap_or(urgency::DEFAULT_SOON_MINUTES) as i64,
+        ),
+    });
+    if let Some(max_requests) = cli.max_requests {
+        client.set_request_budget(max_requests);
+    }
+    let project_scope = if cli.project_scope.is_empty() {
+        config.as_ref().and_then(|config| config.sync.project_scope())
     } else {
-        // Try to load from config file
-        match Config::load() {
-            Ok(mut config) => {
-                println!("📁 Found configuration file ~/.ticktick.toml");
-
-                // Check if we already have a stored access token
-                if let Some(stored_token) = &config.ticktick.access_token {
-                    println!("✅ Using stored access token from configuration file");
-                    let mut client = TickTickClient::new(
-                        config.ticktick.client_id.clone(),
-                        config.ticktick.client_secret.clone(),
-                        config.ticktick.redirect_uri.clone(),
-                    );
-                    client.access_token = Some(stored_token.clone());
-
-                    // Test if the token still works by trying to fetch projects
-                    println!("🔍 Verifying stored access token...");
-                    // println!("🌐 About to make HTTP request to verify token...");
-                    match client.get_projects().await {
-                        Ok(_) => {
-                            println!("✅ Stored access token is valid");
-                            client
-                        }
-                        Err(_) => {
-                            println!("❌ Stored access token is invalid or expired, requesting new one...");
-                            config.ticktick.access_token = None; // Clear invalid token
-
-                            let client_id = config.ticktick.client_id.clone();
-                            let client_secret = config.ticktick.client_secret.clone();
-                            let redirect_uri = config.ticktick.redirect_uri.clone();
-
-                            let mut client =
-                                TickTickClient::new(client_id, client_secret, redirect_uri);
-
-                            // Perform OAuth flow
-                            perform_oauth_flow(&mut client, &mut config).await?;
-                            client
-                        }
-                    }
-                } else {
-                    println!("🔑 No stored access token found, initiating OAuth flow...");
-
-                    let client_id = config.ticktick.client_id.clone();
-                    let client_secret = config.ticktick.client_secret.clone();
-                    let redirect_uri = config.ticktick.redirect_uri.clone();
-
-                    let mut client = TickTickClient::new(client_id, client_secret, redirect_uri);
-
-                    // Perform OAuth flow
-                    perform_oauth_flow(&mut client, &mut config).await?;
-                    client
-                }
-            }
-            Err(_) => {
-                // Fallback to interactive authentication
-                let (client, _config) = interactive_auth().await?;
-                client
+        Some(cli.project_scope.clone())
+    };
+    if let Some(project_scope) = project_scope {
+        client.set_project_scope(&project_scope);
+    }
+    if let Some(seconds) = config
+        .as_ref()
+        .and_then(|config| config.http.pool_idle_timeout)
+    {
+        client.set_pool_idle_timeout(Duration::from_secs(seconds));
+    }
+    client.set_strict_parse(cli.strict_parse);
+    client.set_force(cli.force);
+
+    // Running a command again after re-authorizing would repeat the writes that went through
+    // before the token was rejected, so commands that change anything check the token first
+    // and only read-only commands are retried
+    let read_only = is_read_only(&command);
+    if !read_only && !client.verify_token().await? {
+        reauthorize(&mut client, config.as_mut()).await?;
+    }
+    // Commands that run for a while, e.g. `capture`, would otherwise outlive the access token
+    let _token_refresh = client.refresh_token_in_background()?;
+    let mut result = dispatch(&client, command.clone()).await;
+    if result.is_err() && client.token_rejected() {
+        if read_only {
+            reauthorize(&mut client, config.as_mut()).await?;
+            result = dispatch(&client, command).await;
+        } else {
+            eprintln!(
+                "❌ The access token was rejected partway through. Changes made before that were \
+                 kept and the command wasn't repeated; run it again to finish."
+            );
+        }
+    }
+
+    print_parse_report(&client.take_parse_report(), cli.strict_parse);
+    if let Some(path) = &cli.metrics_file {
+        if let Err(e) = paths::write_atomic(path, &METRICS.render()) {
+            eprintln!("⚠️ Failed to write metrics: {}", e);
+        }
+    }
+    result
+}
+
+/// Commands that only read, so running them again repeats nothing
+fn is_read_only(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Today
+            | Command::Show { .. }
+            | Command::Explain { .. }
+            | Command::List { .. }
+            | Command::Blocked { .. }
+            | Command::Ready { .. }
+            | Command::Week { .. }
+            | Command::Month { .. }
+            | Command::Digest { notify: None, .. }
+    )
+}
+
+/// Authorize again after the API rejected the token
+async fn reauthorize(client: &mut TickTickClient, config: Option<&mut Config>) -> Result<()> {
+    // A token from the environment would win over a newly stored one again on the next run
+    if env_access_token().is_some() {
+        return Err(anyhow!(
+            "TickTick rejected the access token in TICKTICK_ACCESS_TOKEN, it has expired or \
+             been revoked. Set a new one, or unset it to sign in with the configuration file \
+             (`tick init`)."
+        ));
+    }
+    let config = config.ok_or_else(|| anyhow!("No configuration to authorize with, run `tick init`"))?;
+    eprintln!("❌ Stored access token is invalid or expired, requesting new one...");
+    perform_oauth_flow(client, config).await
+}
+
+async fn dispatch(client: &TickTickClient, command: Command) -> Result<()> {
+    match command {
+        Command::Today => commands::run_today(client).await,
+        Command::Add(args) => commands::run_add(client, args).await,
+        Command::Capture { project } => commands::run_capture(client, project.as_deref()).await,
+        Command::Edit(args) => commands::run_edit(client, args).await,
+        Command::Show { task_id } => commands::run_show(client, &task_id).await,
+        Command::Explain { task_id } => commands::run_explain(client, &task_id).await,
+        Command::Note { task_id, text } => commands::run_note(client, &task_id, &text.join(" ")).await,
+        Command::Done { task_id } => commands::run_done(client, &task_id).await,
+        Command::Delete { task_id, yes } => commands::run_delete(client, &task_id, yes).await,
+        Command::Snooze {
+            task_id,
+            when: Some(when),
+            ..
+        } => commands::run_snooze(client, &task_id, &when).await,
+        Command::Snooze {
+            task_id, within, ..
+        } => commands::run_snooze_auto(client, &task_id, within).await,
+        Command::Bulk(args) => commands::run_bulk(client, args).await,
+        Command::Reorder(args) => commands::run_reorder(client, args).await,
+        Command::Blocked { filter } => {
+            commands::run_dependency_view(client, false, filter.as_deref()).await
+        }
+        Command::Ready { filter } => {
+            commands::run_dependency_view(client, true, filter.as_deref()).await
+        }
+        Command::Week { next, prev } => {
+            commands::run_week(client, i64::from(next) - i64::from(prev)).await
+        }
+        Command::Month {
+            completed,
+            next,
+            prev,
+        } => commands::run_month(client, completed, i32::from(next) - i32::from(prev)).await,
+        Command::Digest { format, notify } => commands::run_digest(client, format, notify).await,
+        Command::Import {
+            source: ImportSource::Ics(args),
+        } => commands::run_import_ics(client, args).await,
+        Command::Todos {
+            action: TodosAction::Scan(args),
+        } => commands::run_todos_scan(client, args).await,
+        Command::Github {
+            action: GithubAction::Sync(args),
+        } => commands::run_github_sync(client, args).await,
+        Command::Mail {
+            action: MailAction::Fetch(args),
+        } => commands::run_mail_fetch(client, args).await,
+        Command::Mqtt(args) => commands::run_mqtt(client, args).await,
+        Command::Track {
+            action: TrackAction::Start { task_id },
+        } => commands::run_track_start(client, &task_id).await,
+        Command::Track {
+            action: TrackAction::Stop { .. },
+        } => commands::run_track_stop(Some(client)).await,
+        Command::List { name, filter } => {
+            commands::run_list(client, name.as_deref(), filter.as_deref()).await
+        }
+        Command::Init
+        | Command::Log { .. }
+        | Command::Grep(_)
+        | Command::Config { .. }
+        | Command::Paths
+        | Command::Man { .. }
+        | Command::Track {
+            action: TrackAction::Status,
+        }
+        | Command::Stats { .. } => {
+            unreachable!("handled without a client")
+        }
+    }
+}
+
+fn print_parse_report(report: &ParseReport, strict_parse: bool) {
+    if report.skipped > 0 {
+        eprintln!();
+        eprintln!(
+            "⚠️ Skipped {} item(s) that could not be parsed (rerun with --strict-parse to see the offending JSON)",
+            report.skipped
+        );
+    }
+
+    if strict_parse && !report.unknown_fields.is_empty() {
+        eprintln!();
+        eprintln!("🔍 Unknown fields in API responses:");
+        for field in &report.unknown_fields {
+            eprintln!("  {}", field);
+        }
+    }
+}
+
+/// TICKTICK_ACCESS_TOKEN, unless it's unset or empty
+fn env_access_token() -> Option<String> {
+    env::var("TICKTICK_ACCESS_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+/// Build a client from the environment or the configuration file, returning the
+/// configuration too when there is one
+async fn authenticate() -> Result<(TickTickClient, Option<Config>)> {
+    // An access token in the environment is enough on its own. Settings still come from the
+    // configuration file if there is one; only its credentials and stored token are bypassed.
+    if let Some(access_token) = env_access_token() {
+        let config = if Config::exists() {
+            Some(Config::load()?)
+        } else {
+            None
+        };
+        let client = TickTickClient::new(
+            env::var("TICKTICK_CLIENT_ID").unwrap_or_default(),
+            env::var("TICKTICK_CLIENT_SECRET").unwrap_or_default(),
+            env::var("TICKTICK_REDIRECT_URI").unwrap_or_else(|_| config::default_redirect_uri()),
+        );
+        client.set_token(OAuthToken::from_access_token(access_token));
+        eprintln!("✅ Using the access token from TICKTICK_ACCESS_TOKEN");
+        return Ok((client, config));
+    }
+
+    // Try to load from config file
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            if Config::exists() {
+                return Err(e);
             }
+
+            // First run, fall back to the guided setup
+            eprintln!("👋 No configuration found, starting first-run setup...");
+            eprintln!();
+            let (client, config) = run_init().await?;
+            return Ok((client, Some(config)));
         }
     };
 
-    println!();
-    println!("🗓️ Fetching today's tasks...");
-    // println!("🌐 About to make HTTP requests to fetch tasks...");
-    println!();
-
-    match client.get_todays_tasks().await {
-        Ok(tasks) => {
-            if tasks.is_empty() {
-                println!("🎉 No tasks due today! You're all caught up!");
-            } else {
-                println!("📅 You have {} task(s) for today:", tasks.len());
-                println!();
-
-                // We need to get project names for display
-                // println!("🌐 Making additional HTTP request to get project names...");
-                let projects = client.get_projects().await?;
-                let project_map: std::collections::HashMap<String, String> =
-                    projects.into_iter().map(|p| (p.id, p.name)).collect();
-
-                print_tasks_grouped(&tasks, &project_map);
+    let mut client = TickTickClient::new(
+        config.ticktick.client_id.clone(),
+        config.ticktick.client_secret.clone(),
+        config.ticktick.redirect_uri.clone(),
+    );
+
+    // Check if we already have a stored access token
+    if let Some(stored_token) = config.oauth_token()? {
+        client.set_token(stored_token);
+
+        if client.token_needs_refresh() {
+            eprintln!("🔄 Stored access token is about to expire, refreshing...");
+            match client.refresh_access_token(&mut config).await {
+                Ok(()) => eprintln!("✅ Refreshed access token"),
+                Err(e) => eprintln!("⚠️ {}", e),
             }
         }
-        Err(e) => {
-            println!("❌ Error fetching tasks: {}", e);
-        }
+    } else {
+        eprintln!("🔑 No stored access token found, initiating OAuth flow...");
+        perform_oauth_flow(&mut client, &mut config).await?;
     }
 
-    Ok(())
+    Ok((client, Some(config)))
 }
@@ -0,0 +1,123 @@
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the request latency histogram, in seconds
+const LATENCY_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Default)]
+struct Latencies {
+    count: u64,
+    seconds: f64,
+    /// Requests at or below each bound of LATENCY_BUCKETS
+    buckets: [u64; LATENCY_BUCKETS.len()],
+}
+
+/// Counters kept for the lifetime of the process, written out by `--metrics-file`
+#[derive(Default)]
+pub struct Metrics {
+    /// Keyed by operation, e.g. "Get projects", and status code or "error". Operations are a
+    /// fixed set of names, never ids, so the number of series stays bounded.
+    requests: Mutex<BTreeMap<(String, String), Latencies>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+impl Metrics {
+    /// Count an API request; `status` is None when no response arrived
+    pub fn record_request(&self, operation: &str, status: Option<u16>, elapsed: Duration) {
+        let status = status.map_or_else(|| "error".to_string(), |status| status.to_string());
+        let seconds = elapsed.as_secs_f64();
+
+        let mut requests = self.requests.lock().unwrap();
+        let latencies = requests.entry((operation.to_string(), status)).or_default();
+        latencies.count += 1;
+        latencies.seconds += seconds;
+        for (bucket, bound) in latencies.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+    }
+
+    /// Count a lookup answered from the cache, or one that needed the API
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Everything recorded so far in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let requests = self.requests.lock().unwrap();
+        let labels = |operation: &str, status: &str| {
+            format!(
+                "operation=\"{}\",status=\"{}\"",
+                operation.replace('\\', "\\\\").replace('"', "\\\""),
+                status
+            )
+        };
+
+        out.push_str("# HELP tick_api_requests_total Requests sent to the TickTick API\n");
+        out.push_str("# TYPE tick_api_requests_total counter\n");
+        for ((operation, status), latencies) in requests.iter() {
+            let _ = writeln!(
+                out,
+                "tick_api_requests_total{{{}}} {}",
+                labels(operation, status),
+                latencies.count
+            );
+        }
+
+        out.push_str("# HELP tick_api_request_duration_seconds Time until the API responded\n");
+        out.push_str("# TYPE tick_api_request_duration_seconds histogram\n");
+        for ((operation, status), latencies) in requests.iter() {
+            let labels = labels(operation, status);
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(latencies.buckets) {
+                let _ = writeln!(
+                    out,
+                    "tick_api_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "tick_api_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, latencies.count
+            );
+            let _ = writeln!(
+                out,
+                "tick_api_request_duration_seconds_sum{{{}}} {}",
+                labels, latencies.seconds
+            );
+            let _ = writeln!(
+                out,
+                "tick_api_request_duration_seconds_count{{{}}} {}",
+                labels, latencies.count
+            );
+        }
+
+        out.push_str("# HELP tick_cache_lookups_total Task and project lookups by cache result\n");
+        out.push_str("# TYPE tick_cache_lookups_total counter\n");
+        let _ = writeln!(
+            out,
+            "tick_cache_lookups_total{{result=\"hit\"}} {}",
+            self.cache_hits.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "tick_cache_lookups_total{{result=\"miss\"}} {}",
+            self.cache_misses.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
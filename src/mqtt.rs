@@ -0,0 +1,281 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use rumqttc::{AsyncClient, Event, MqttOptions, Outgoing, QoS};
+use serde_json::{json, Value};
+use std::env;
+use std::time::Duration;
+use url::{Host, Url};
+
+use crate::cache::Cache;
+use crate::dates::parse_api_datetime;
+use crate::digest::Digest;
+
+/// Default port for unencrypted MQTT
+const DEFAULT_PORT: u16 = 1883;
+
+/// Environment variable that supplies the broker password, e.g. for cron
+pub const PASSWORD_ENV: &str = "TICK_MQTT_PASSWORD";
+
+/// Broker password from TICK_MQTT_PASSWORD, or else a prompt. Never a command line flag, as
+/// those end up in shell history and process listings.
+pub fn password(username: &str) -> Result<String> {
+    if let Some(password) = env::var(PASSWORD_ENV).ok().filter(|p| !p.is_empty()) {
+        return Ok(password);
+    }
+    rpassword::prompt_password(format!("🔐 MQTT password for {}: ", username)).map_err(|e| {
+        anyhow!(
+            "Failed to read password ({}), set {} to provide it non-interactively",
+            e,
+            PASSWORD_ENV
+        )
+    })
+}
+
+/// Where and how to publish
+pub struct MqttSettings {
+    pub broker: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Prefix for the state topic, e.g. `tick` publishes to `tick/state`
+    pub topic_prefix: String,
+    /// Home Assistant discovery prefix, or None to skip discovery messages
+    pub discovery_prefix: Option<String>,
+}
+
+/// Split `host`, `host:port` or `mqtt://host:port` into host and port. IPv6 addresses go in
+/// brackets, e.g. `[::1]:1883`, and come back without them.
+fn parse_broker(broker: &str) -> Result<(String, u16)> {
+    let with_scheme = if broker.contains("://") {
+        broker.to_string()
+    } else {
+        format!("mqtt://{}", broker)
+    };
+    let url =
+        Url::parse(&with_scheme).map_err(|e| anyhow!("Invalid MQTT broker '{}': {}", broker, e))?;
+    if url.scheme() != "mqtt" {
+        return Err(anyhow!(
+            "Invalid MQTT broker '{}': only mqtt:// is supported",
+            broker
+        ));
+    }
+    let host = match url.host() {
+        Some(Host::Ipv6(address)) => address.to_string(),
+        Some(host) => host.to_string(),
+        None => return Err(anyhow!("Invalid MQTT broker '{}': no host", broker)),
+    };
+    Ok((host, url.port().unwrap_or(DEFAULT_PORT)))
+}
+
+/// Task counts and the next task due, as published to `<prefix>/state`
+pub fn state_payload(cache: &Cache, digest: &Digest) -> Value {
+    let now = Local::now();
+    let next_due = cache
+        .tasks
+        .values()
+        .flatten()
+        .filter_map(|task| {
+            let due = task.due_date.as_deref().and_then(parse_api_datetime)?;
+            // All-day tasks due today count as upcoming for the whole day
+            let upcoming = if task.is_all_day.unwrap_or(false) {
+                due.date_naive() >= now.date_naive()
+            } else {
+                due >= now
+            };
+            upcoming.then_some((due, task))
+        })
+        .min_by_key(|(due, _)| *due);
+
+    json!({
+        "open": digest.stats.open,
+        "overdue": digest.overdue.len(),
+        "due_today": digest.today.len(),
+        "blocked": digest.stats.blocked,
+        "next_due_title": next_due.map(|(_, task)| task.title.clone()),
+        "next_due_at": next_due.map(|(due, _)| due.to_rfc3339()),
+        "updated_at": now.to_rfc3339(),
+    })
+}
+
+/// Home Assistant discovery messages as (topic, config) pairs
+fn discovery_messages(settings: &MqttSettings, discovery_prefix: &str) -> Vec<(String, Value)> {
+    let state_topic = format!("{}/state", settings.topic_prefix);
+    let object_prefix = settings.topic_prefix.replace('/', "_");
+    let device = json!({
+        "identifiers": [format!("tick_{}", object_prefix)],
+        "name": "TickTick",
+        "manufacturer": "tick",
+    });
+
+    let sensors = [
+        (
+            "open",
+            "Open tasks",
+            "{{ value_json.open }}",
+            "mdi:format-list-checks",
+        ),
+        (
+            "overdue",
+            "Overdue tasks",
+            "{{ value_json.overdue }}",
+            "mdi:alert-circle",
+        ),
+        (
+            "due_today",
+            "Tasks due today",
+            "{{ value_json.due_today }}",
+            "mdi:calendar-today",
+        ),
+        (
+            "blocked",
+            "Blocked tasks",
+            "{{ value_json.blocked }}",
+            "mdi:lock",
+        ),
+    ];
+
+    let mut messages: Vec<(String, Value)> = sensors
+        .iter()
+        .map(|(key, name, template, icon)| {
+            (
+                format!(
+                    "{}/sensor/{}/{}/config",
+                    discovery_prefix, object_prefix, key
+                ),
+                json!({
+                    "name": name,
+                    "unique_id": format!("{}_{}", object_prefix, key),
+                    "state_topic": state_topic,
+                    "value_template": template,
+                    "unit_of_measurement": "tasks",
+                    "state_class": "measurement",
+                    "icon": icon,
+                    "device": device,
+                }),
+            )
+        })
+        .collect();
+
+    messages.push((
+        format!(
+            "{}/sensor/{}/next_due/config",
+            discovery_prefix, object_prefix
+        ),
+        json!({
+            "name": "Next task",
+            "unique_id": format!("{}_next_due", object_prefix),
+            "state_topic": state_topic,
+            "value_template": "{{ value_json.next_due_title or 'None' }}",
+            "json_attributes_topic": state_topic,
+            "json_attributes_template": "{{ {'due_at': value_json.next_due_at} | tojson }}",
+            "icon": "mdi:clock-outline",
+            "device": device,
+        }),
+    ));
+    messages.push((
+        format!(
+            "{}/sensor/{}/next_due_at/config",
+            discovery_prefix, object_prefix
+        ),
+        json!({
+            "name": "Next task due",
+            "unique_id": format!("{}_next_due_at", object_prefix),
+            "state_topic": state_topic,
+            "value_template": "{{ value_json.next_due_at }}",
+            "device_class": "timestamp",
+            "device": device,
+        }),
+    ));
+    messages.push((
+        format!(
+            "{}/binary_sensor/{}/has_overdue/config",
+            discovery_prefix, object_prefix
+        ),
+        json!({
+            "name": "Tasks overdue",
+            "unique_id": format!("{}_has_overdue", object_prefix),
+            "state_topic": state_topic,
+            "value_template": "{{ 'ON' if value_json.overdue > 0 else 'OFF' }}",
+            "device_class": "problem",
+            "device": device,
+        }),
+    ));
+
+    messages
+}
+
+/// Publish the state (and discovery config) as retained messages, then disconnect
+pub async fn publish(settings: &MqttSettings, state: &Value) -> Result<()> {
+    let (host, port) = parse_broker(&settings.broker)?;
+    let mut options = MqttOptions::new(format!("tick-{}", std::process::id()), host, port);
+    options.set_keep_alive(Duration::from_secs(10));
+    if let Some(username) = &settings.username {
+        options.set_credentials(
+            username.clone(),
+            settings.password.clone().unwrap_or_default(),
+        );
+    }
+
+    let mut messages = Vec::new();
+    if let Some(discovery_prefix) = &settings.discovery_prefix {
+        messages.extend(discovery_messages(settings, discovery_prefix));
+    }
+    messages.push((format!("{}/state", settings.topic_prefix), state.clone()));
+
+    // Room for every message plus the disconnect, so queueing never waits on the event loop
+    let (client, mut eventloop) = AsyncClient::new(options, messages.len() + 1);
+    for (topic, payload) in messages {
+        client
+            .publish(topic, QoS::AtLeastOnce, true, payload.to_string())
+            .await
+            .map_err(|e| anyhow!("Failed to queue MQTT message: {}", e))?;
+    }
+    client
+        .disconnect()
+        .await
+        .map_err(|e| anyhow!("Failed to queue MQTT disconnect: {}", e))?;
+
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Outgoing(Outgoing::Disconnect)) => return Ok(()),
+            Ok(_) => {}
+            Err(e) => {
+                return Err(anyhow!(
+                    "MQTT connection to {} failed: {}",
+                    settings.broker,
+                    e
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brokers() {
+        let parsed = |broker| parse_broker(broker).unwrap();
+        assert_eq!(parsed("localhost"), ("localhost".to_string(), 1883));
+        assert_eq!(parsed("localhost:1884"), ("localhost".to_string(), 1884));
+        assert_eq!(
+            parsed("mqtt://10.0.0.2:8883"),
+            ("10.0.0.2".to_string(), 8883)
+        );
+
+        assert_eq!(parsed("[::1]"), ("::1".to_string(), 1883));
+        assert_eq!(parsed("[::1]:1884"), ("::1".to_string(), 1884));
+        assert_eq!(
+            parsed("mqtt://[fd00::2]:8883"),
+            ("fd00::2".to_string(), 8883)
+        );
+    }
+
+    #[test]
+    fn invalid_brokers() {
+        assert!(parse_broker("localhost:port").is_err());
+        assert!(parse_broker("localhost:70000").is_err());
+        assert!(parse_broker("[::1").is_err());
+        assert!(parse_broker("https://localhost").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::config::NotifyConfig;
+use crate::digest::{Digest, DigestTask};
+use crate::i18n::{self, text};
+
+/// Chat service a digest can be posted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NotifyTarget {
+    Slack,
+    Discord,
+}
+
+impl fmt::Display for NotifyTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NotifyTarget::Slack => "Slack",
+            NotifyTarget::Discord => "Discord",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Discord rejects embed fields longer than this
+const DISCORD_FIELD_LIMIT: usize = 1024;
+
+/// Slack rejects section text longer than this
+const SLACK_SECTION_LIMIT: usize = 3000;
+
+/// Slack rejects messages with more blocks than this
+const SLACK_BLOCK_LIMIT: usize = 50;
+
+/// `text` cut to at most `limit` characters, ending in `…` if it was cut
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(limit - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Slack mrkdwn treats `&`, `<` and `>` as control characters
+fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn task_list(tasks: &[DigestTask], show_date: bool, empty: &str) -> Vec<String> {
+    if tasks.is_empty() {
+        return vec![format!("_{}_", empty)];
+    }
+    tasks
+        .iter()
+        .map(|task| format!("• {}", task.line(show_date)))
+        .collect()
+}
+
+/// The digest's sections as (heading, lines) pairs shared by every target
+fn sections(digest: &Digest) -> Vec<(String, Vec<String>)> {
+    let text = text();
+    let mut sections = vec![
+        (
+            format!("{} ({})", text.overdue, digest.overdue.len()),
+            task_list(&digest.overdue, true, text.nothing_overdue),
+        ),
+        (
+            format!("{} ({})", text.due_today, digest.today.len()),
+            task_list(&digest.today, false, text.nothing_due_today),
+        ),
+    ];
+    for (date, tasks) in &digest.upcoming {
+        sections.push((
+            i18n::day_heading(*date),
+            task_list(tasks, false, text.nothing_due),
+        ));
+    }
+    sections
+}
+
+fn stats_line(digest: &Digest) -> String {
+    digest
+        .stat_lines()
+        .iter()
+        .map(|(label, value)| format!("{}: {}", label, value))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// A section's heading and lines as mrkdwn texts that each fit in one Slack section, split
+/// between lines. The heading goes on the first.
+fn slack_section_texts(heading: &str, lines: &[String]) -> Vec<String> {
+    let mut texts = Vec::new();
+    let mut text = format!("*{}*", escape_slack(heading));
+    for line in lines {
+        let line = truncate(&escape_slack(line), SLACK_SECTION_LIMIT);
+        if text.chars().count() + 1 + line.chars().count() > SLACK_SECTION_LIMIT {
+            texts.push(std::mem::replace(&mut text, line));
+        } else {
+            text.push('\n');
+            text.push_str(&line);
+        }
+    }
+    texts.push(text);
+    texts
+}
+
+/// Slack incoming-webhook payload using Block Kit
+fn slack_payload(digest: &Digest) -> Value {
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": digest.title() },
+    })];
+
+    let texts: Vec<String> = sections(digest)
+        .iter()
+        .flat_map(|(heading, lines)| slack_section_texts(heading, lines))
+        .collect();
+    // Room for the header, the divider, the stats and a note about what was left out
+    let room = SLACK_BLOCK_LIMIT - 4;
+    let left_out = texts.len().saturating_sub(room);
+    for text in texts.into_iter().take(room) {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": text },
+        }));
+    }
+    if left_out > 0 {
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!("_{}_", (text().sections_left_out)(left_out)),
+            },
+        }));
+    }
+
+    blocks.push(json!({ "type": "divider" }));
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{ "type": "mrkdwn", "text": stats_line(digest) }],
+    }));
+
+    json!({
+        // Shown in notifications and by clients that can't render blocks
+        "text": digest.title(),
+        "blocks": blocks,
+    })
+}
+
+/// Discord webhook payload with a single embed, red when something is overdue
+fn discord_payload(digest: &Digest) -> Value {
+    let fields: Vec<Value> = sections(digest)
+        .into_iter()
+        .map(|(heading, lines)| {
+            let value = truncate(&lines.join("\n"), DISCORD_FIELD_LIMIT);
+            json!({ "name": heading, "value": value, "inline": false })
+        })
+        .collect();
+
+    let color = if digest.overdue.is_empty() {
+        0x4a90e2
+    } else {
+        0xd0021b
+    };
+
+    json!({
+        "embeds": [{
+            "title": digest.title(),
+            "color": color,
+            "fields": fields,
+            "footer": { "text": stats_line(digest) },
+        }],
+    })
+}
+
+/// Post the digest to the webhook configured for `target`
+pub async fn send_digest(
+    target: NotifyTarget,
+    config: &NotifyConfig,
+    digest: &Digest,
+) -> Result<()> {
+    let (webhook, payload) = match target {
+        NotifyTarget::Slack => (config.slack.as_ref(), slack_payload(digest)),
+        NotifyTarget::Discord => (config.discord.as_ref(), discord_payload(digest)),
+    };
+    let webhook = webhook.ok_or_else(|| {
+        anyhow!(
+            "No {} webhook configured, add [notify.{}] webhook_url = \"...\" to the configuration file (see `tick paths`)",
+            target,
+            target.to_string().to_lowercase()
+        )
+    })?;
+
+    let response = reqwest::Client::new()
+        .post(&webhook.webhook_url)
+        .json(&payload)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("{} webhook failed: {} - {}", target, status, body));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_text_is_cut_with_an_ellipsis() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("exactly10!", 10), "exactly10!");
+        assert_eq!(
+            truncate("one character too many", 21),
+            "one character too ma…"
+        );
+    }
+
+    #[test]
+    fn slack_sections_are_split_between_lines() {
+        // Three lines fit in a section, but only two after the heading
+        let line = format!("• {}", "x".repeat(995));
+        let lines = vec![line.clone(); 7];
+        let texts = slack_section_texts("Due today (7)", &lines);
+
+        assert!(texts
+            .iter()
+            .all(|text| text.chars().count() <= SLACK_SECTION_LIMIT));
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts[0], format!("*Due today (7)*\n{}\n{}", line, line));
+        assert_eq!(texts[1], format!("{}\n{}\n{}", line, line, line));
+        assert_eq!(
+            texts
+                .iter()
+                .map(|text| text.matches('•').count())
+                .sum::<usize>(),
+            7
+        );
+    }
+
+    #[test]
+    fn slack_lines_longer_than_a_section_are_cut() {
+        let texts = slack_section_texts("Overdue (1)", &["y".repeat(5000)]);
+        assert_eq!(texts.len(), 2);
+        assert_eq!(texts[1].chars().count(), SLACK_SECTION_LIMIT);
+        assert!(texts[1].ends_with('…'));
+    }
+
+    #[test]
+    fn slack_text_is_escaped_before_measuring() {
+        let texts = slack_section_texts("R&D <today>", &["• a < b".to_string()]);
+        assert_eq!(texts, ["*R&amp;D &lt;today&gt;*\n• a &lt; b"]);
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use directories::{BaseDirs, ProjectDirs};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("", "", "tick").ok_or_else(|| anyhow!("Could not find home directory"))
+}
+
+/// Where the configuration lived before tick used platform directories
+pub fn legacy_config_file() -> Option<PathBuf> {
+    BaseDirs::new().map(|dirs| dirs.home_dir().join(".ticktick.toml"))
+}
+
+/// Platform configuration file, e.g. `~/.config/tick/config.toml` on Linux
+pub fn platform_config_file() -> Result<PathBuf> {
+    Ok(project_dirs()?.config_dir().join("config.toml"))
+}
+
+/// Configuration file in use: the platform location, unless only a legacy
+/// `~/.ticktick.toml` exists, which keeps being used until it is moved
+pub fn config_file() -> Result<PathBuf> {
+    let config_file = platform_config_file()?;
+    match legacy_config_file() {
+        Some(legacy) if !config_file.exists() && legacy.exists() => Ok(legacy),
+        _ => Ok(config_file),
+    }
+}
+
+/// Local copy of projects and tasks, safe to delete
+pub fn cache_file() -> Result<PathBuf> {
+    Ok(project_dirs()?.cache_dir().join("cache.json"))
+}
+
+/// Directory for logs and history that tick appends to over time; platforms without a
+/// state directory use the local data directory
+pub fn state_dir() -> Result<PathBuf> {
+    let dirs = project_dirs()?;
+    Ok(dirs
+        .state_dir()
+        .unwrap_or_else(|| dirs.data_local_dir())
+        .to_path_buf())
+}
+
+/// The file a path ends up at, following symlinks; the path itself if it doesn't exist yet
+fn resolve(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn open_lock(path: &Path) -> Result<(File, PathBuf)> {
+    let lock_path = resolve(path).with_extension("lock");
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| anyhow!("Failed to open lock file {}: {}", lock_path.display(), e))?;
+    Ok((file, lock_path))
+}
+
+/// Exclusive advisory lock on `<path>.lock`, held until the returned file is dropped. Other tick
+/// processes wait for it; programs that don't take the lock aren't held up.
+pub fn lock(path: &Path) -> Result<File> {
+    let (file, lock_path) = open_lock(path)?;
+    file.lock()
+        .map_err(|e| anyhow!("Failed to lock {}: {}", lock_path.display(), e))?;
+    Ok(file)
+}
+
+/// Write a temporary file next to `path` and rename it over the original, so readers never see
+/// a half-written file. Concurrent writers are serialized by the lock; the last one wins.
+fn replace(path: &Path, contents: &str, private: bool) -> Result<()> {
+    let _lock = lock(path)?;
+    replace_locked(path, contents, private)
+}
+
+/// `replace` for callers that already hold the file's lock. A symlink, e.g. to a config file
+/// kept in a dotfiles repository, is written through rather than replaced by a regular file.
+fn replace_locked(path: &Path, contents: &str, private: bool) -> Result<()> {
+    let path = &resolve(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| anyhow!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let draft_path = path.with_extension(format!("tmp.{}", std::process::id()));
+
+    let mut options = OpenOptions::new();
+    options.create(true).truncate(true).write(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    let written = options
+        .open(&draft_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())
+                .and_then(|_| file.sync_all())
+        })
+        .and_then(|_| fs::rename(&draft_path, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&draft_path);
+        return Err(anyhow!("Failed to write {}: {}", path.display(), e));
+    }
+    Ok(())
+}
+
+/// Replace a file's contents in one step, creating its directory
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    replace(path, contents, false)
+}
+
+/// `write_atomic` for a file whose lock the caller already holds, e.g. the cache
+pub fn write_atomic_locked(path: &Path, contents: &str) -> Result<()> {
+    replace_locked(path, contents, false)
+}
+
+/// Write a file that may hold secrets, creating its directory and limiting it to the current
+/// user on Unix
+pub fn write_private(path: &Path, contents: &str) -> Result<()> {
+    replace(path, contents, true)
+}
+
+/// Append lines to a log under its lock, in a single write so entries from concurrent
+/// processes never interleave
+pub fn append(path: &Path, lines: &str) -> Result<()> {
+    let _lock = lock(path)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(lines.as_bytes())
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+pub fn audit_log() -> Result<PathBuf> {
+    Ok(state_dir()?.join("audit.log"))
+}
+
+pub fn history_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("history.jsonl"))
+}
+
+pub fn ics_imports_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("ics_imports.json"))
+}
+
+pub fn github_sync_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("github_issues.json"))
+}
+
+/// Time tracked with `tick track`, one JSON object per stopped timer
+pub fn time_log() -> Result<PathBuf> {
+    Ok(state_dir()?.join("time_log.jsonl"))
+}
+
+/// The running `tick track` timer, removed when it's stopped
+pub fn timer_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("timer.json"))
+}
+
+/// Tasks shown by the last listing, for positional references like `tick done 3`
+pub fn last_listing_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("last_listing.json"))
+}
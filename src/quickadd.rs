@@ -0,0 +1,185 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+
+use crate::types::Priority;
+
+/// A task typed on one line, e.g. `Call the bank ~Finance #phone !high @tomorrow`
+#[derive(Debug, Default, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    /// `~name`, or `~"Multi word name"`
+    pub project: Option<String>,
+    /// `#tag`, may be repeated
+    pub tags: Vec<String>,
+    /// `!high`, `!medium`, `!low` or `!none`
+    pub priority: Option<Priority>,
+    /// `@tomorrow`, `@3d`, `@2024-05-01` or `@"May 1 5pm"`
+    pub due: Option<String>,
+}
+
+/// Split into words, keeping `"quoted text"` after a marker together. A word starting with a
+/// backslash is returned without the backslash and is never treated as a marker.
+fn words(line: &str) -> Result<Vec<(String, bool)>> {
+    let mut words = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let literal = c == '\\';
+        let mut word = if literal {
+            String::new()
+        } else {
+            c.to_string()
+        };
+
+        if !literal && "~#@".contains(c) && chars.peek() == Some(&'"') {
+            chars.next();
+            let mut quoted = String::new();
+            let mut closed = false;
+            for c in chars.by_ref() {
+                if c == '"' {
+                    closed = true;
+                    break;
+                }
+                quoted.push(c);
+            }
+            if !closed {
+                return Err(anyhow!("Missing closing quote after '{}\"{}'", c, quoted));
+            }
+            if quoted.trim().is_empty() {
+                return Err(anyhow!("Empty quotes after '{}'", c));
+            }
+            word.push_str(&quoted);
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                word.push(c);
+            }
+        }
+        words.push((word, literal));
+    }
+    Ok(words)
+}
+
+pub fn parse(line: &str) -> Result<QuickAdd> {
+    let mut quick_add = QuickAdd::default();
+    let mut title: Vec<String> = Vec::new();
+
+    for (word, literal) in words(line)? {
+        let mut chars = word.chars();
+        let (marker, value) = match chars.next() {
+            Some(marker) if !literal && word.len() > 1 => (marker, chars.as_str()),
+            _ => {
+                title.push(word);
+                continue;
+            }
+        };
+
+        match marker {
+            '~' => quick_add.project = Some(value.to_string()),
+            '#' => quick_add.tags.push(value.to_string()),
+            '@' => quick_add.due = Some(value.to_string()),
+            // An unknown word after `!` is left in the title, e.g. "Ship it !now"
+            '!' => match Priority::from_str(value, true) {
+                Ok(priority) => quick_add.priority = Some(priority),
+                Err(_) => title.push(word),
+            },
+            _ => title.push(word),
+        }
+    }
+
+    quick_add.title = title.join(" ");
+    if quick_add.title.is_empty() {
+        return Err(anyhow!(
+            "A task needs a title besides ~project, #tags, !priority and @date"
+        ));
+    }
+    Ok(quick_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quick_add(title: &str, project: Option<&str>, tags: &[&str], due: Option<&str>) -> QuickAdd {
+        QuickAdd {
+            title: title.to_string(),
+            project: project.map(str::to_string),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            priority: None,
+            due: due.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn markers() {
+        let cases = [
+            ("Call the bank", quick_add("Call the bank", None, &[], None)),
+            (
+                "Call the bank ~Finance #phone @tomorrow",
+                quick_add(
+                    "Call the bank",
+                    Some("Finance"),
+                    &["phone"],
+                    Some("tomorrow"),
+                ),
+            ),
+            (
+                "~\"Home Office\" Order chair #buy #work @\"May 1 5pm\"",
+                quick_add(
+                    "Order chair",
+                    Some("Home Office"),
+                    &["buy", "work"],
+                    Some("May 1 5pm"),
+                ),
+            ),
+            (
+                "Pay invoice \\#42 to \\~Bob",
+                quick_add("Pay invoice #42 to ~Bob", None, &[], None),
+            ),
+            (
+                "Lone # and @ stay",
+                quick_add("Lone # and @ stay", None, &[], None),
+            ),
+            (
+                "Later ~Work ~Home",
+                quick_add("Later", Some("Home"), &[], None),
+            ),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse(line).unwrap(), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn priorities() {
+        let parsed = parse("Ship it !high").unwrap();
+        assert_eq!(parsed.priority, Some(Priority::High));
+        assert_eq!(parsed.title, "Ship it");
+        assert_eq!(parse("Ship it !now").unwrap().title, "Ship it !now");
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            (
+                "Order chair ~\"Home Office",
+                "Missing closing quote after '~\"Home Office'",
+            ),
+            ("Order chair @\"", "Missing closing quote after '@\"'"),
+            ("Order chair ~\"  \"", "Empty quotes after '~'"),
+            (
+                "~Work #phone",
+                "A task needs a title besides ~project, #tags, !priority and @date",
+            ),
+            (
+                "",
+                "A task needs a title besides ~project, #tags, !priority and @date",
+            ),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse(line).unwrap_err().to_string(), expected, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::types::Task;
+
+/// Gap left between tasks when a project is renumbered, so later moves only touch one task
+const STEP: i64 = 1 << 20;
+
+/// Where to move a task within its project
+#[derive(Debug, Clone, Copy)]
+pub enum Move {
+    Up(usize),
+    Down(usize),
+    Top,
+    Bottom,
+}
+
+/// Open tasks in the order the app shows them; tasks without a sortOrder go last
+pub fn in_app_order<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Vec<&'a Task> {
+    let mut ordered: Vec<&Task> = tasks.into_iter().filter(|task| task.status == 0).collect();
+    ordered.sort_by_key(|task| task.sort_order.unwrap_or(i64::MAX));
+    ordered
+}
+
+/// The task's new position and the (task id, sortOrder) updates that move `ordered[from]` there.
+/// Only the moved task changes when its new neighbours leave room; otherwise every task whose
+/// value differs is renumbered.
+pub fn plan(ordered: &[&Task], from: usize, movement: Move) -> (usize, Vec<(String, i64)>) {
+    let last = ordered.len().saturating_sub(1);
+    let to = match movement {
+        Move::Up(places) => from.saturating_sub(places),
+        Move::Down(places) => from.saturating_add(places).min(last),
+        Move::Top => 0,
+        Move::Bottom => last,
+    };
+    if to == from {
+        return (to, Vec::new());
+    }
+
+    let mut reordered = ordered.to_vec();
+    let task = reordered.remove(from);
+    reordered.insert(to, task);
+
+    let before = to.checked_sub(1).map(|index| reordered[index].sort_order);
+    let after = reordered.get(to + 1).map(|task| task.sort_order);
+    let slot = match (before, after) {
+        (Some(Some(before)), Some(Some(after))) => after
+            .checked_sub(before)
+            .filter(|gap| *gap >= 2)
+            .map(|gap| before + gap / 2),
+        (None, Some(Some(after))) => after.checked_sub(STEP),
+        (Some(Some(before)), None) => before.checked_add(STEP),
+        _ => None,
+    };
+
+    let updates = match slot {
+        Some(sort_order) => vec![(task.id.clone(), sort_order)],
+        None => reordered
+            .iter()
+            .zip((0..).map(|index: i64| index * STEP))
+            .filter(|(task, sort_order)| task.sort_order != Some(*sort_order))
+            .map(|(task, sort_order)| (task.id.clone(), sort_order))
+            .collect(),
+    };
+    (to, updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, sort_order: Option<i64>) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "projectId": "project",
+            "title": id,
+            "status": 0,
+            "sortOrder": sort_order,
+        }))
+        .unwrap()
+    }
+
+    fn tasks(sort_orders: &[Option<i64>]) -> Vec<Task> {
+        let ids = ["a", "b", "c", "d"];
+        sort_orders
+            .iter()
+            .zip(ids)
+            .map(|(sort_order, id)| task(id, *sort_order))
+            .collect()
+    }
+
+    fn updates(list: &[(&str, i64)]) -> Vec<(String, i64)> {
+        list.iter()
+            .map(|(id, sort_order)| (id.to_string(), *sort_order))
+            .collect()
+    }
+
+    #[test]
+    fn moves_past_the_edges_stop_there() {
+        let tasks = tasks(&[Some(0), Some(STEP), Some(2 * STEP)]);
+        let ordered: Vec<&Task> = tasks.iter().collect();
+        assert_eq!(plan(&ordered, 0, Move::Up(1)), (0, Vec::new()));
+        assert_eq!(plan(&ordered, 0, Move::Top), (0, Vec::new()));
+        assert_eq!(plan(&ordered, 2, Move::Down(3)), (2, Vec::new()));
+        assert_eq!(plan(&ordered, 2, Move::Bottom), (2, Vec::new()));
+        assert_eq!(
+            plan(&ordered, 2, Move::Up(10)),
+            (0, updates(&[("c", -STEP)]))
+        );
+        assert_eq!(
+            plan(&ordered, 0, Move::Down(10)),
+            (2, updates(&[("a", 3 * STEP)]))
+        );
+    }
+
+    #[test]
+    fn a_gap_between_neighbours_moves_only_the_task() {
+        let tasks = tasks(&[Some(0), Some(100), Some(200)]);
+        let ordered: Vec<&Task> = tasks.iter().collect();
+        assert_eq!(plan(&ordered, 2, Move::Up(1)), (1, updates(&[("c", 50)])));
+        assert_eq!(
+            plan(&ordered, 0, Move::Down(1)),
+            (1, updates(&[("a", 150)]))
+        );
+    }
+
+    #[test]
+    fn no_room_renumbers_the_project() {
+        // Equal sortOrders leave no gap at all
+        let equal = tasks(&[Some(10), Some(10), Some(10)]);
+        let ordered: Vec<&Task> = equal.iter().collect();
+        assert_eq!(
+            plan(&ordered, 2, Move::Up(1)),
+            (1, updates(&[("a", 0), ("c", STEP), ("b", 2 * STEP)]))
+        );
+
+        // Neighbours one apart leave no value between them; unchanged tasks aren't sent
+        let tight = tasks(&[Some(0), Some(1), Some(2)]);
+        let ordered: Vec<&Task> = tight.iter().collect();
+        assert_eq!(
+            plan(&ordered, 2, Move::Up(1)),
+            (1, updates(&[("c", STEP), ("b", 2 * STEP)]))
+        );
+    }
+
+    #[test]
+    fn missing_or_extreme_sort_orders_renumber() {
+        let unsorted = tasks(&[Some(0), None]);
+        let ordered: Vec<&Task> = unsorted.iter().collect();
+        assert_eq!(
+            plan(&ordered, 0, Move::Bottom),
+            (1, updates(&[("b", 0), ("a", STEP)]))
+        );
+
+        let full = tasks(&[Some(0), Some(i64::MAX)]);
+        let ordered: Vec<&Task> = full.iter().collect();
+        assert_eq!(
+            plan(&ordered, 0, Move::Bottom),
+            (1, updates(&[("b", 0), ("a", STEP)]))
+        );
+    }
+}
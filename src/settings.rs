@@ -0,0 +1,734 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use toml::{Table, Value};
+
+use crate::config::{Config, WEEKDAY_NAMES};
+use crate::{crypt, github, mail, paths};
+
+/// Values a configuration key accepts
+#[derive(Debug, Clone, Copy)]
+pub enum ValueKind {
+    /// Any non-empty string
+    Text,
+    /// An http or https URL
+    Url,
+    /// One of a fixed set of lowercase words
+    Choice(&'static [&'static str]),
+    /// A whole number, zero or more
+    Count,
+    /// A TCP port, 1 to 65535
+    Port,
+    /// true or false
+    Flag,
+    /// An RFC 3339 date and time, e.g. 2024-05-01T17:30:00Z
+    DateTime,
+}
+
+/// A key that `tick config get/set` understands
+pub struct KeyDef {
+    /// Dotted path into the TOML document; `*` matches any single segment
+    pub path: &'static str,
+    pub kind: ValueKind,
+    /// Why the key can't be changed with `tick config set`, if it can't
+    pub read_only: Option<&'static str>,
+}
+
+pub const KEYS: &[KeyDef] = &[
+    KeyDef {
+        path: "ticktick.client_id",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "ticktick.client_secret",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "ticktick.redirect_uri",
+        kind: ValueKind::Url,
+        read_only: None,
+    },
+    KeyDef {
+        path: "ticktick.token_storage",
+        kind: ValueKind::Choice(&["file", "keyring", "encrypted"]),
+        read_only: Some(
+            "use `tick config encrypt` or `tick config decrypt` so stored tokens are migrated",
+        ),
+    },
+    KeyDef {
+        path: "ticktick.access_token",
+        kind: ValueKind::Text,
+        read_only: Some("it's written by tick when you authorize"),
+    },
+    KeyDef {
+        path: "ticktick.refresh_token",
+        kind: ValueKind::Text,
+        read_only: Some("it's written by tick when you authorize"),
+    },
+    KeyDef {
+        path: "ticktick.token_expires_at",
+        kind: ValueKind::DateTime,
+        read_only: Some("it's written by tick when you authorize"),
+    },
+    KeyDef {
+        path: "display.time_style",
+        kind: ValueKind::Choice(&["relative", "absolute", "iso"]),
+        read_only: None,
+    },
+    KeyDef {
+        path: "display.language",
+        kind: ValueKind::Choice(&["en", "de"]),
+        read_only: None,
+    },
+    KeyDef {
+        path: "display.first_day_of_week",
+        kind: ValueKind::Choice(&WEEKDAY_NAMES),
+        read_only: None,
+    },
+    KeyDef {
+        path: "display.content_lines",
+        kind: ValueKind::Count,
+        read_only: None,
+    },
+    KeyDef {
+        path: "display.project_order",
+        kind: ValueKind::Choice(&["app", "name"]),
+        read_only: None,
+    },
+    KeyDef {
+        path: "display.task_order",
+        kind: ValueKind::Choice(&["app", "priority", "due", "title"]),
+        read_only: None,
+    },
+    KeyDef {
+        path: "theme.soon_minutes",
+        kind: ValueKind::Count,
+        read_only: None,
+    },
+    KeyDef {
+        path: "sync.project_scope",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "http.pool_idle_timeout",
+        kind: ValueKind::Count,
+        read_only: None,
+    },
+    KeyDef {
+        path: "notify.slack.webhook_url",
+        kind: ValueKind::Url,
+        read_only: None,
+    },
+    KeyDef {
+        path: "notify.discord.webhook_url",
+        kind: ValueKind::Url,
+        read_only: None,
+    },
+    KeyDef {
+        path: "todos.project",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "github.token",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "hooks.on_complete_command",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "hooks.bell",
+        kind: ValueKind::Flag,
+        read_only: None,
+    },
+    KeyDef {
+        path: "mail.host",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "mail.port",
+        kind: ValueKind::Port,
+        read_only: None,
+    },
+    KeyDef {
+        path: "mail.username",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "mail.password",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "mail.folder",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "mail.project",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+    KeyDef {
+        path: "filters.*.query",
+        kind: ValueKind::Text,
+        read_only: None,
+    },
+];
+
+fn matches_path(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let path: Vec<&str> = path.split('.').collect();
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(&path)
+            .all(|(pattern, segment)| *pattern == "*" || pattern == segment)
+}
+
+fn find_key(path: &str) -> Result<&'static KeyDef> {
+    KEYS.iter()
+        .find(|key| matches_path(key.path, path))
+        .ok_or_else(|| {
+            let known: Vec<&str> = KEYS.iter().map(|key| key.path).collect();
+            anyhow!(
+                "Unknown config key '{}'. Known keys:\n  {}",
+                path,
+                known.join("\n  ")
+            )
+        })
+}
+
+/// Check a raw value against the key's kind, returning the value to store
+const PORT_RANGE: &str = "must be a port from 1 to 65535";
+
+fn parse_value(kind: ValueKind, raw: &str) -> Result<Value, String> {
+    match kind {
+        ValueKind::Text if raw.trim().is_empty() => Err("must not be empty".to_string()),
+        ValueKind::Text => Ok(Value::String(raw.to_string())),
+        ValueKind::Url => match url::Url::parse(raw) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {
+                Ok(Value::String(raw.to_string()))
+            }
+            _ => Err("must be an http or https URL".to_string()),
+        },
+        ValueKind::Choice(choices) => {
+            let value = raw.to_lowercase();
+            if choices.contains(&value.as_str()) {
+                Ok(Value::String(value))
+            } else {
+                Err(format!("must be one of: {}", choices.join(", ")))
+            }
+        }
+        ValueKind::Count => raw
+            .parse::<u32>()
+            .map(|count| Value::Integer(count.into()))
+            .map_err(|_| "must be a whole number of zero or more".to_string()),
+        ValueKind::Port => match raw.parse::<u16>() {
+            Ok(port) if port > 0 => Ok(Value::Integer(port.into())),
+            _ => Err(PORT_RANGE.to_string()),
+        },
+        ValueKind::Flag => match raw.to_lowercase().as_str() {
+            "true" | "yes" | "on" => Ok(Value::Boolean(true)),
+            "false" | "no" | "off" => Ok(Value::Boolean(false)),
+            _ => Err("must be true or false".to_string()),
+        },
+        ValueKind::DateTime => chrono::DateTime::parse_from_rfc3339(raw)
+            .map(|_| Value::String(raw.to_string()))
+            .map_err(|_| "must be a date and time such as 2024-05-01T17:30:00Z".to_string()),
+    }
+}
+
+/// Check a value already in the document against the key's kind
+fn check_value(kind: ValueKind, value: &Value) -> Result<(), String> {
+    match (kind, value) {
+        (ValueKind::Count, Value::Integer(count)) if u32::try_from(*count).is_ok() => Ok(()),
+        (ValueKind::Count, _) => Err("must be a whole number of zero or more".to_string()),
+        (ValueKind::Port, Value::Integer(port)) if (1..=65535).contains(port) => Ok(()),
+        (ValueKind::Port, _) => Err(PORT_RANGE.to_string()),
+        (ValueKind::Flag, Value::Boolean(_)) => Ok(()),
+        (ValueKind::Flag, _) => Err("must be true or false".to_string()),
+        (kind, Value::String(raw)) => parse_value(kind, raw).map(|_| ()),
+        (_, Value::Table(_)) => Err("expected a value, not a table".to_string()),
+        (_, value) => Err(format!("expected a string, not {}", value.type_str())),
+    }
+}
+
+fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let mut value = table.get(segments.next()?)?;
+    for segment in segments {
+        value = value.as_table()?.get(segment)?;
+    }
+    Some(value)
+}
+
+fn remove(table: &mut Table, path: &str) {
+    let (parents, last) = path.rsplit_once('.').unwrap_or(("", path));
+    let mut table = table;
+    for segment in parents.split('.').filter(|segment| !segment.is_empty()) {
+        match table.get_mut(segment).and_then(Value::as_table_mut) {
+            Some(child) => table = child,
+            None => return,
+        }
+    }
+    table.remove(last);
+}
+
+fn insert(table: &mut Table, path: &str, value: Value) -> Result<()> {
+    let segments: Vec<&str> = path.split('.').collect();
+    let (last, parents) = segments
+        .split_last()
+        .ok_or_else(|| anyhow!("Empty config key"))?;
+
+    let mut table = table;
+    for segment in parents {
+        table = table
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow!("'{}' in '{}' is not a table", segment, path))?;
+    }
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+/// Dotted paths of every non-table value in the document
+fn leaves(table: &Table, prefix: &str) -> Vec<(String, Value)> {
+    let mut found = Vec::new();
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Table(child) => found.extend(leaves(child, &path)),
+            value => found.push((path, value.clone())),
+        }
+    }
+    found
+}
+
+/// Keys holding secrets, with their names in the system keyring. They are stored the way
+/// `ticktick.token_storage` says, like the tokens.
+const SECRETS: &[(&str, &str)] = &[
+    ("mail.password", mail::PASSWORD_SECRET),
+    ("github.token", github::TOKEN_SECRET),
+];
+
+/// Whether a key holds a secret, whose value shouldn't be printed
+pub fn is_secret(path: &str) -> bool {
+    SECRETS.iter().any(|(secret, _)| *secret == path)
+}
+
+/// Keys that must be set whenever their table is present
+const REQUIRED: &[&str] = &[
+    "ticktick.client_id",
+    "ticktick.client_secret",
+    "mail.host",
+    "mail.username",
+    "filters.*.query",
+];
+
+/// Something wrong with one key of the document
+struct Problem {
+    path: String,
+    message: String,
+}
+
+impl Problem {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        Problem {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Number of single-character edits between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The known key an unknown one was most likely meant to be
+fn suggestion(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('.').collect();
+    KEYS.iter()
+        .filter(|key| key.path.split('.').count() == segments.len())
+        .map(|key| {
+            // Fill wildcards with the segment used, so filters.work.qeury suggests filters.work.query
+            key.path
+                .split('.')
+                .zip(&segments)
+                .map(|(pattern, segment)| if pattern == "*" { *segment } else { pattern })
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .map(|candidate| (edit_distance(&candidate, path), candidate))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Dotted paths of every table in the document, the root included as ""
+fn tables(table: &Table, prefix: &str) -> Vec<String> {
+    let mut found = vec![prefix.to_string()];
+    for (key, value) in table {
+        if let Value::Table(child) = value {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            found.extend(tables(child, &path));
+        }
+    }
+    found
+}
+
+/// Everything wrong with the document: unknown keys, values of the wrong type or format,
+/// missing required keys and options that contradict each other
+fn problems(table: &Table) -> Vec<Problem> {
+    let mut found = Vec::new();
+
+    for (path, value) in leaves(table, "") {
+        match KEYS.iter().find(|key| matches_path(key.path, &path)) {
+            Some(key) => {
+                if let Err(e) = check_value(key.kind, &value) {
+                    found.push(Problem::new(&path, e));
+                }
+            }
+            None => found.push(Problem::new(
+                &path,
+                match suggestion(&path) {
+                    Some(known) => format!("unknown key, did you mean {}?", known),
+                    None => "unknown key".to_string(),
+                },
+            )),
+        }
+    }
+
+    if !table.contains_key("ticktick") {
+        found.push(Problem::new(
+            "ticktick",
+            "missing, run `tick init` to set up your credentials",
+        ));
+    }
+    let present = tables(table, "");
+    for required in REQUIRED {
+        let Some((parent, name)) = required.rsplit_once('.') else {
+            continue;
+        };
+        for path in present.iter().filter(|path| matches_path(parent, path)) {
+            let has_key = lookup(table, path)
+                .and_then(Value::as_table)
+                .is_some_and(|table| table.contains_key(name));
+            if !has_key {
+                found.push(Problem::new(&format!("{}.{}", path, name), "missing"));
+            }
+        }
+    }
+
+    let storage = lookup(table, "ticktick.token_storage").and_then(Value::as_str);
+    let tokens = ["ticktick.access_token", "ticktick.refresh_token"];
+    let secrets = SECRETS.iter().map(|(path, _)| *path);
+    for path in tokens.into_iter().chain(secrets) {
+        let Some(token) = lookup(table, path).and_then(Value::as_str) else {
+            continue;
+        };
+        match storage {
+            // Secrets in the file are still used when the keyring has none
+            Some("keyring") if tokens.contains(&path) => found.push(Problem::new(
+                path,
+                "token_storage is keyring, so this token is ignored; remove it",
+            )),
+            None | Some("file") if crypt::is_encrypted(token) => found.push(Problem::new(
+                path,
+                "the value is encrypted but token_storage is file; set token_storage = \
+                 \"encrypted\"",
+            )),
+            _ => {}
+        }
+    }
+
+    found
+}
+
+fn report(problems: Vec<String>) -> anyhow::Error {
+    anyhow!("Invalid configuration:\n  {}", problems.join("\n  "))
+}
+
+/// Check the document against every known key, then that it deserializes as a configuration
+pub fn validate(table: &Table) -> Result<Config> {
+    let found = problems(table);
+    if !found.is_empty() {
+        return Err(report(
+            found
+                .into_iter()
+                .map(|problem| format!("{}: {}", problem.path, problem.message))
+                .collect(),
+        ));
+    }
+
+    Value::Table(table.clone())
+        .try_into()
+        .map_err(|e| anyhow!("Invalid configuration: {}", e))
+}
+
+/// Line of the key or table at `path` in TOML text, counting from 1. Follows `[table]`
+/// headers and dotted keys, which covers files written by `tick init` and by hand.
+fn line_of(content: &str, path: &str) -> Option<usize> {
+    let dotted = |text: &str| {
+        text.split('.')
+            .map(|segment| segment.trim().trim_matches('"'))
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+
+    let mut table = String::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let full = if let Some(header) = line.strip_prefix('[') {
+            table = dotted(
+                header
+                    .trim_start_matches('[')
+                    .split(']')
+                    .next()
+                    .unwrap_or(""),
+            );
+            table.clone()
+        } else if let Some((key, _)) = line.split_once('=') {
+            match table.as_str() {
+                "" => dotted(key),
+                table => format!("{}.{}", table, dotted(key)),
+            }
+        } else {
+            continue;
+        };
+        if full == path {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+/// Parse and validate the text of a configuration file, giving the line of each problem.
+/// Missing keys point at their table.
+pub fn validate_text(content: &str) -> Result<Config> {
+    let table: Table = content
+        .parse()
+        .map_err(|e| anyhow!("Failed to parse TOML: {}", e))?;
+
+    let mut found: Vec<(Option<usize>, Problem)> = problems(&table)
+        .into_iter()
+        .map(|problem| {
+            let line = line_of(content, &problem.path).or_else(|| {
+                let (parent, _) = problem.path.rsplit_once('.')?;
+                line_of(content, parent)
+            });
+            (line, problem)
+        })
+        .collect();
+    if !found.is_empty() {
+        found.sort_by_key(|(line, _)| line.unwrap_or(usize::MAX));
+        return Err(report(
+            found
+                .into_iter()
+                .map(|(line, problem)| match line {
+                    Some(line) => format!("line {}: {}: {}", line, problem.path, problem.message),
+                    None => format!("{}: {}", problem.path, problem.message),
+                })
+                .collect(),
+        ));
+    }
+
+    validate(&table)
+}
+
+/// Parse the configuration file as a TOML document
+pub fn read_document() -> Result<Table> {
+    let config_path = Config::config_path()?;
+    let content = fs::read_to_string(&config_path).map_err(|e| {
+        anyhow!(
+            "Failed to read config file {}: {}\n\nRun `tick init` to create one.",
+            config_path.display(),
+            e
+        )
+    })?;
+    content.parse().map_err(|e| {
+        anyhow!(
+            "Failed to parse config file {}: {}",
+            config_path.display(),
+            e
+        )
+    })
+}
+
+/// Current value of a key, or None if it isn't set
+pub fn get(table: &Table, path: &str) -> Result<Option<String>> {
+    find_key(path)?;
+    Ok(lookup(table, path).map(|value| match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }))
+}
+
+/// Validate and set a key, then validate and save the whole file. Secrets are asked for when
+/// `raw` is None, and encrypted or put in the keyring when the tokens are.
+pub fn set(mut table: Table, path: &str, raw: Option<&str>) -> Result<()> {
+    let key = find_key(path)?;
+    if let Some(reason) = key.read_only {
+        return Err(anyhow!("{} can't be set directly, {}", path, reason));
+    }
+    let secret = SECRETS
+        .iter()
+        .find(|(secret, _)| *secret == path)
+        .map(|(_, name)| *name);
+    let raw = match (raw, secret) {
+        (Some(raw), _) => raw.to_string(),
+        (None, Some(_)) => rpassword::prompt_password(format!("🔐 {}: ", path))
+            .map_err(|e| anyhow!("Failed to read {}: {}", path, e))?,
+        (None, None) => return Err(anyhow!("{} needs a value", path)),
+    };
+
+    let mut value =
+        parse_value(key.kind, &raw).map_err(|e| anyhow!("Invalid value for {}: {}", path, e))?;
+    if let Some(name) = secret {
+        match lookup(&table, "ticktick.token_storage").and_then(Value::as_str) {
+            Some("encrypted") => value = Value::String(crypt::encrypt(&raw)?),
+            Some("keyring") => {
+                validate(&table)?.ticktick.keyring_set(name, &raw)?;
+                remove(&mut table, path);
+                let config_path = Config::config_path()?;
+                let content = toml::to_string_pretty(&table)
+                    .map_err(|e| anyhow!("Failed to serialize config: {}", e))?;
+                return paths::write_private(&config_path, &content);
+            }
+            _ => {}
+        }
+    }
+    insert(&mut table, path, value)?;
+    validate(&table)?;
+
+    let config_path = Config::config_path()?;
+    let content =
+        toml::to_string_pretty(&table).map_err(|e| anyhow!("Failed to serialize config: {}", e))?;
+    paths::write_private(&config_path, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    const MINIMAL: &str = "[ticktick]\nclient_id = \"id\"\nclient_secret = \"secret\"\n";
+
+    #[test]
+    fn saved_config_loads_again() {
+        let content = format!("{}\n[display]\nfirst_day_of_week = \"sunday\"\n", MINIMAL);
+        let config = validate_text(&content).unwrap();
+        assert_eq!(config.display.first_day_of_week, Some(Weekday::Sun));
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert!(saved.contains("first_day_of_week = \"sunday\""));
+        let reloaded = validate_text(&saved).unwrap();
+        assert_eq!(reloaded.display.first_day_of_week, Some(Weekday::Sun));
+    }
+
+    fn problems_in(extra: &str) -> Vec<(String, String)> {
+        let table: Table = format!("{}{}", MINIMAL, extra).parse().unwrap();
+        problems(&table)
+            .into_iter()
+            .map(|problem| (problem.path, problem.message))
+            .collect()
+    }
+
+    #[test]
+    fn minimal_config_has_no_problems() {
+        assert!(problems_in("").is_empty());
+    }
+
+    #[test]
+    fn problems_name_the_key() {
+        let found = problems_in("[display]\ncontent_line = 3\n");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "display.content_line");
+        assert!(found[0].1.contains("did you mean display.content_lines?"));
+
+        let found = problems_in("[mail]\nhost = \"imap.example.com\"\n");
+        assert_eq!(
+            found,
+            [("mail.username".to_string(), "missing".to_string())]
+        );
+
+        let found = problems_in("[filters.work]\nqeury = \"#work\"\n");
+        assert_eq!(found.len(), 2);
+        assert!(found[0].1.contains("did you mean filters.work.query?"));
+        assert_eq!(
+            found[1],
+            ("filters.work.query".to_string(), "missing".to_string())
+        );
+    }
+
+    #[test]
+    fn ports_must_be_in_range() {
+        let mail = "[mail]\nhost = \"imap.example.com\"\nusername = \"me\"\n";
+        assert!(problems_in(&format!("{}port = 993\n", mail)).is_empty());
+        for port in ["0", "65536", "-1"] {
+            let found = problems_in(&format!("{}port = {}\n", mail, port));
+            assert_eq!(
+                found,
+                [("mail.port".to_string(), PORT_RANGE.to_string())],
+                "port {}",
+                port
+            );
+        }
+        assert!(parse_value(ValueKind::Port, "993").is_ok());
+        assert!(parse_value(ValueKind::Port, "70000").is_err());
+    }
+
+    #[test]
+    fn line_of_follows_headers_and_dotted_keys() {
+        let content = "# tick\ntheme.soon_minutes = 5\n[ticktick]\nclient_id = \"id\"\n\n\
+                       [filters.\"work\"]\nquery = \"#work\"\n";
+        assert_eq!(line_of(content, "theme.soon_minutes"), Some(2));
+        assert_eq!(line_of(content, "ticktick"), Some(3));
+        assert_eq!(line_of(content, "ticktick.client_id"), Some(4));
+        assert_eq!(line_of(content, "filters.work.query"), Some(7));
+        assert_eq!(line_of(content, "ticktick.client_secret"), None);
+    }
+
+    #[test]
+    fn suggestions_are_close_known_keys() {
+        assert_eq!(
+            suggestion("display.time_styel").as_deref(),
+            Some("display.time_style")
+        );
+        assert_eq!(
+            suggestion("filters.work.qeury").as_deref(),
+            Some("filters.work.query")
+        );
+        assert_eq!(suggestion("display.something_else"), None);
+    }
+}
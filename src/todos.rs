@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `TODO(tick): text`, but not `TODO(tick:<id>): text` which already has a task
+static UNTRACKED: Lazy<Regex> = Lazy::new(|| Regex::new(r"TODO\(tick\):[ \t]*([^\r\n]*)").unwrap());
+
+/// Comment openers, by the languages that use them. The text before a marker on its line must
+/// end with one of its file's openers, after either nothing or some code and a space, so
+/// mentions of the marker in strings, docs or prose are left alone.
+static C_COMMENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|\s)(?://[/!]?|/\*+!?|\*)\s*$").unwrap());
+static HASH_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s)#+\s*$").unwrap());
+static DASH_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s)--+\s*$").unwrap());
+static SEMICOLON_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s);+\s*$").unwrap());
+static HTML_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s)<!--\s*$").unwrap());
+/// Files whose language isn't known accept any of the openers above
+static ANY_COMMENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|\s)(?://[/!]?|/\*+!?|\*|#+|--+|;+|<!--)\s*$").unwrap());
+
+/// The comment syntax of a file, from its extension
+fn comment_syntax(path: &Path) -> &'static Regex {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "java" | "kt" | "kts" | "scala" | "go"
+        | "swift" | "dart" | "js" | "jsx" | "mjs" | "ts" | "tsx" | "php" | "css" | "scss"
+        | "less" | "proto" | "zig" => &C_COMMENT,
+        "py" | "rb" | "sh" | "bash" | "zsh" | "fish" | "nu" | "pl" | "r" | "ps1" | "yml"
+        | "yaml" | "toml" | "cmake" | "mk" | "dockerfile" | "tf" | "nix" => &HASH_COMMENT,
+        "sql" | "lua" | "hs" | "elm" | "ada" => &DASH_COMMENT,
+        "lisp" | "el" | "clj" | "cljs" | "scm" | "ini" | "asm" => &SEMICOLON_COMMENT,
+        "md" | "markdown" | "html" | "htm" | "xml" | "svg" | "vue" | "svelte" => &HTML_COMMENT,
+        _ => &ANY_COMMENT,
+    }
+}
+
+/// Directories that hold dependencies or build output rather than source
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// Larger files are assumed to be generated and skipped
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// A `TODO(tick):` comment that doesn't have a task yet
+pub struct Todo {
+    pub line: usize,
+    pub text: String,
+    /// Byte offset of the `TODO(tick):` marker in the file
+    offset: usize,
+}
+
+/// Comment text without trailing block comment closers such as `*/` or `-->`
+fn clean_text(text: &str) -> String {
+    text.trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim()
+        .to_string()
+}
+
+fn todos_in(content: &str, comment: &Regex) -> Vec<Todo> {
+    UNTRACKED
+        .captures_iter(content)
+        .filter_map(|captures| {
+            let marker = captures.get(0)?;
+            let line_start = content[..marker.start()].rfind('\n').map_or(0, |i| i + 1);
+            if !comment.is_match(&content[line_start..marker.start()]) {
+                return None;
+            }
+            let text = clean_text(captures.get(1)?.as_str());
+            Some(Todo {
+                line: content[..marker.start()].matches('\n').count() + 1,
+                text,
+                offset: marker.start(),
+            })
+        })
+        .filter(|todo| !todo.text.is_empty())
+        .collect()
+}
+
+fn visit(path: &Path, found: &mut Vec<(PathBuf, Vec<Todo>)>) -> Result<()> {
+    let entries =
+        fs::read_dir(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let mut entries: Vec<_> = entries.filter_map(|entry| entry.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref()) {
+                visit(&entry.path(), found)?;
+            }
+        } else if file_type.is_file()
+            && entry
+                .metadata()
+                .is_ok_and(|meta| meta.len() <= MAX_FILE_SIZE)
+        {
+            // Binary and non-UTF-8 files fail to read as text and are skipped
+            if let Ok(content) = fs::read_to_string(entry.path()) {
+                let todos = todos_in(&content, comment_syntax(&entry.path()));
+                if !todos.is_empty() {
+                    found.push((entry.path(), todos));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every file under `root` with untracked TODO comments, skipping hidden and build directories
+pub fn scan(root: &Path) -> Result<Vec<(PathBuf, Vec<Todo>)>> {
+    let mut found = Vec::new();
+    if root.is_file() {
+        let content = fs::read_to_string(root)
+            .map_err(|e| anyhow!("Failed to read {}: {}", root.display(), e))?;
+        let todos = todos_in(&content, comment_syntax(root));
+        if !todos.is_empty() {
+            found.push((root.to_path_buf(), todos));
+        }
+    } else {
+        visit(root, &mut found)?;
+    }
+    Ok(found)
+}
+
+/// Rewrite each created TODO as `TODO(tick:<task id>):` so later scans skip it
+pub fn mark_tracked(path: &Path, created: &[(&Todo, String)]) -> Result<()> {
+    let mut content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut created: Vec<&(&Todo, String)> = created.iter().collect();
+    created.sort_by_key(|(todo, _)| std::cmp::Reverse(todo.offset));
+    for (todo, task_id) in created {
+        let marker = todo.offset..todo.offset + "TODO(tick):".len();
+        if content.get(marker.clone()) != Some("TODO(tick):") {
+            return Err(anyhow!("{} changed during the scan", path.display()));
+        }
+        content.replace_range(marker, &format!("TODO(tick:{}):", task_id));
+    }
+
+    fs::write(path, content).map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(content: &str, file: &str) -> Vec<String> {
+        todos_in(content, comment_syntax(Path::new(file)))
+            .into_iter()
+            .map(|todo| todo.text)
+            .collect()
+    }
+
+    #[test]
+    fn only_comments_are_matched() {
+        let rust = "fn main() {\n    // TODO(tick): first\n    run(); // TODO(tick): second\n    \
+                    /* TODO(tick): third */\n    let s = \"// TODO(tick): in a string\";\n}\n\
+                    /// A `TODO(tick):` mention in docs\n";
+        assert_eq!(texts(rust, "main.rs"), ["first", "second", "third"]);
+
+        assert_eq!(texts("x = 1  # TODO(tick): python\n", "a.py"), ["python"]);
+        assert_eq!(texts("-- TODO(tick): query\n", "a.sql"), ["query"]);
+        assert!(texts("// TODO(tick): not a python comment\n", "a.py").is_empty());
+    }
+
+    #[test]
+    fn markdown_only_counts_html_comments() {
+        let markdown = "Write `TODO(tick): <title>` in a comment.\n\
+                        ```sh\n# TODO(tick): example\n```\n<!-- TODO(tick): real -->\n";
+        assert_eq!(texts(markdown, "README.md"), ["real"]);
+    }
+
+    #[test]
+    fn tracked_and_empty_todos_are_skipped() {
+        let content = "// TODO(tick:abc123): done already\n// TODO(tick):\n# TODO(tick): kept\n";
+        assert_eq!(texts(content, "notes"), ["kept"]);
+    }
+}
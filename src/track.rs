@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::paths;
+use crate::types::Task;
+
+/// The timer started by `tick track start`, until it's stopped
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Timer {
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub started_at: DateTime<Local>,
+}
+
+impl Timer {
+    pub fn elapsed(&self) -> Duration {
+        Local::now() - self.started_at
+    }
+
+    /// The entry this timer would log if it were stopped now
+    pub fn stopped_now(&self) -> Entry {
+        Entry {
+            task_id: self.task_id.clone(),
+            project_id: self.project_id.clone(),
+            title: self.title.clone(),
+            started_at: self.started_at,
+            stopped_at: Local::now(),
+        }
+    }
+}
+
+/// A stopped timer in the time log
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Entry {
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub started_at: DateTime<Local>,
+    pub stopped_at: DateTime<Local>,
+}
+
+impl Entry {
+    pub fn duration(&self) -> Duration {
+        self.stopped_at - self.started_at
+    }
+}
+
+/// The part of the time from `started_at` to `stopped_at` that falls at or after `since`
+pub fn time_since(
+    started_at: DateTime<Local>,
+    stopped_at: DateTime<Local>,
+    since: DateTime<Local>,
+) -> Duration {
+    (stopped_at - started_at.max(since)).max(Duration::zero())
+}
+
+/// A duration as hours and minutes, e.g. "1h 05m" or "25m"
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// The running timer, if any
+pub fn running() -> Result<Option<Timer>> {
+    let path = paths::timer_file()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Failed to read timer {}: {}", path.display(), e))?;
+    let timer = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse timer {}: {}", path.display(), e))?;
+    Ok(Some(timer))
+}
+
+/// Start timing a task. The caller stops any running timer first.
+pub fn start(task: &Task) -> Result<Timer> {
+    let timer = Timer {
+        task_id: task.id.clone(),
+        project_id: task.project_id.clone(),
+        title: task.title.clone(),
+        started_at: Local::now(),
+    };
+    paths::write_atomic(
+        &paths::timer_file()?,
+        &serde_json::to_string_pretty(&timer)?,
+    )?;
+    Ok(timer)
+}
+
+/// Stop the running timer and append it to the time log, returning the logged entry
+pub fn stop() -> Result<Option<Entry>> {
+    let Some(timer) = running()? else {
+        return Ok(None);
+    };
+
+    let entry = timer.stopped_now();
+    log(&entry)?;
+    Ok(Some(entry))
+}
+
+/// Append the entry of the running timer to the time log and remove the timer
+pub fn log(entry: &Entry) -> Result<()> {
+    paths::append(
+        &paths::time_log()?,
+        &format!("{}\n", serde_json::to_string(entry)?),
+    )?;
+
+    let timer_path = paths::timer_file()?;
+    fs::remove_file(&timer_path)
+        .map_err(|e| anyhow!("Failed to remove timer {}: {}", timer_path.display(), e))
+}
+
+/// Read every logged entry, skipping lines that can't be parsed
+pub fn read_entries() -> Result<Vec<Entry>> {
+    let log_path = paths::time_log()?;
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&log_path)
+        .map_err(|e| anyhow!("Failed to read time log {}: {}", log_path.display(), e))?;
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 2, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn time_since_clips_to_the_start() {
+        assert_eq!(time_since(at(8), at(10), at(9)), Duration::hours(1));
+        assert_eq!(time_since(at(9), at(10), at(8)), Duration::hours(1));
+        assert_eq!(time_since(at(6), at(7), at(8)), Duration::zero());
+    }
+}
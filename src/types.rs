@@ -1,3 +1,4 @@
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -9,7 +10,44 @@ pub struct TokenResponse {
     pub scope: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// An access token along with what's needed to refresh it
+#[derive(Debug, Clone)]
+pub struct OAuthToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthToken {
+    /// A bare access token with no known expiry and no way to refresh it
+    pub fn from_access_token(access_token: String) -> Self {
+        Self {
+            access_token,
+            refresh_token: None,
+            expires_at: None,
+        }
+    }
+
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at - Utc::now() <= margin)
+            .unwrap_or(false)
+    }
+}
+
+impl From<TokenResponse> for OAuthToken {
+    fn from(response: TokenResponse) -> Self {
+        Self {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: response
+                .expires_in
+                .map(|seconds| Utc::now() + Duration::seconds(seconds as i64)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Project {
     pub id: String,
     pub name: String,
@@ -21,9 +59,11 @@ pub struct Project {
     pub view_mode: Option<String>,
     pub permission: Option<String>,
     pub kind: Option<String>,
+    #[serde(rename = "sortOrder")]
+    pub sort_order: Option<i64>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ChecklistItem {
     pub id: Option<String>,
     pub title: String,
@@ -40,7 +80,18 @@ pub struct ChecklistItem {
     pub time_zone: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Attachment {
+    /// Not always sent; a missing id mustn't drop the whole task
+    pub id: Option<String>,
+    #[serde(rename = "fileName")]
+    pub file_name: Option<String>,
+    #[serde(rename = "fileType")]
+    pub file_type: Option<String>,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Task {
     pub id: String,
     #[serde(rename = "projectId")]
@@ -52,6 +103,8 @@ pub struct Task {
     pub completed_time: Option<String>,
     pub content: Option<String>,
     pub desc: Option<String>,
+    #[serde(rename = "modifiedTime")]
+    pub modified_time: Option<String>,
     #[serde(rename = "dueDate")]
     pub due_date: Option<String>,
     pub items: Option<Vec<ChecklistItem>>,
@@ -64,19 +117,76 @@ pub struct Task {
     #[serde(rename = "startDate")]
     pub start_date: Option<String>,
     pub status: i32, // 0 = Normal, 2 = Completed
+    pub tags: Option<Vec<String>>,
+    pub attachments: Option<Vec<Attachment>>,
+    #[serde(rename = "commentCount")]
+    pub comment_count: Option<u32>,
     #[serde(rename = "timeZone")]
     pub time_zone: Option<String>,
 }
 
+/// Task priority as used by the API: 0 = None, 1 = Low, 3 = Medium, 5 = High
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn value(self) -> i32 {
+        match self {
+            Priority::None => 0,
+            Priority::Low => 1,
+            Priority::Medium => 3,
+            Priority::High => 5,
+        }
+    }
+}
+
+/// Body of a create-task request
+#[derive(Debug, Default, Serialize)]
+pub struct NewTask {
+    pub title: String,
+    #[serde(rename = "projectId", skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(rename = "isAllDay", skip_serializing_if = "Option::is_none")]
+    pub is_all_day: Option<bool>,
+    #[serde(rename = "startDate", skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(rename = "dueDate", skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(rename = "timeZone", skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<NewChecklistItem>>,
+}
+
+/// Checklist item in a create-task request
+#[derive(Debug, Serialize)]
+pub struct NewChecklistItem {
+    pub title: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ProjectData {
     pub project: Project,
+    #[serde(default)]
     pub tasks: Vec<Task>,
     pub columns: Option<Vec<serde_json::Value>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct InboxData {
+    #[serde(default)]
     pub tasks: Vec<Task>,
     pub columns: Option<Vec<serde_json::Value>>,
 }
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Duration, Local};
+use std::cmp::Ordering;
+
+use crate::dates::parse_api_datetime;
+use crate::types::Task;
+
+/// Minutes before a timed task is due that it counts as due soon, unless `theme.soon_minutes`
+/// says otherwise
+pub const DEFAULT_SOON_MINUTES: u64 = 60;
+
+/// How pressing a task's due time is, most pressing first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Urgency {
+    Overdue,
+    /// Due within the soon threshold
+    Soon,
+    /// Due later today
+    Today,
+    Later,
+}
+
+impl Urgency {
+    /// Classify a due time against `now`. All-day tasks are only overdue once their day has
+    /// passed, and are never due soon.
+    pub fn classify(
+        due: DateTime<Local>,
+        all_day: bool,
+        now: DateTime<Local>,
+        soon: Duration,
+    ) -> Self {
+        let today = now.date_naive();
+        if all_day {
+            return match due.date_naive().cmp(&today) {
+                Ordering::Less => Urgency::Overdue,
+                Ordering::Equal => Urgency::Today,
+                Ordering::Greater => Urgency::Later,
+            };
+        }
+
+        if due < now {
+            Urgency::Overdue
+        } else if due - now <= soon {
+            Urgency::Soon
+        } else if due.date_naive() == today {
+            Urgency::Today
+        } else {
+            Urgency::Later
+        }
+    }
+
+    /// Urgency of a task's due date right now, None when it has no due date
+    pub fn of_task(task: &Task, soon: Duration) -> Option<Self> {
+        let due = task.due_date.as_deref().and_then(parse_api_datetime)?;
+        Some(Self::classify(
+            due,
+            task.is_all_day == Some(true),
+            Local::now(),
+            soon,
+        ))
+    }
+
+    fn style(self) -> &'static str {
+        // ANSI codes: \x1b[1m = bold, \x1b[31m = red, \x1b[38;5;208m = orange, \x1b[33m = yellow
+        match self {
+            Urgency::Overdue => "\x1b[1m\x1b[31m",
+            Urgency::Soon => "\x1b[38;5;208m",
+            Urgency::Today => "\x1b[33m",
+            Urgency::Later => "",
+        }
+    }
+
+    /// `text` in this urgency's color; later due times are left plain
+    pub fn paint(self, text: &str) -> String {
+        match self.style() {
+            "" => text.to_string(),
+            style => format!("{}{}\x1b[0m", style, text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 6, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn timed(due: DateTime<Local>, now: DateTime<Local>) -> Urgency {
+        Urgency::classify(due, false, now, Duration::minutes(60))
+    }
+
+    fn all_day(due: DateTime<Local>, now: DateTime<Local>) -> Urgency {
+        Urgency::classify(due, true, now, Duration::minutes(60))
+    }
+
+    #[test]
+    fn timed_tasks_at_the_boundaries() {
+        let now = at(12, 9, 0);
+        assert_eq!(timed(now - Duration::seconds(1), now), Urgency::Overdue);
+        // Due this very moment, or exactly at the end of the soon window, is still soon
+        assert_eq!(timed(now, now), Urgency::Soon);
+        assert_eq!(timed(at(12, 10, 0), now), Urgency::Soon);
+        assert_eq!(
+            timed(at(12, 10, 0) + Duration::seconds(1), now),
+            Urgency::Today
+        );
+        assert_eq!(timed(at(12, 23, 59), now), Urgency::Today);
+        assert_eq!(timed(at(13, 0, 0), now), Urgency::Later);
+    }
+
+    #[test]
+    fn soon_reaches_past_midnight() {
+        let now = at(12, 23, 30);
+        assert_eq!(timed(at(13, 0, 15), now), Urgency::Soon);
+        assert_eq!(timed(at(13, 0, 45), now), Urgency::Later);
+    }
+
+    #[test]
+    fn all_day_tasks_go_by_the_day() {
+        let now = at(12, 23, 59);
+        assert_eq!(all_day(at(11, 0, 0), now), Urgency::Overdue);
+        // Midnight has passed, but the day hasn't
+        assert_eq!(all_day(at(12, 0, 0), now), Urgency::Today);
+        // Never soon, even when its day starts within the soon window
+        assert_eq!(all_day(at(13, 0, 0), now), Urgency::Later);
+    }
+}